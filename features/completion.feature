@@ -0,0 +1,63 @@
+Feature: Shell completion
+
+  @turtle
+  Scenario Outline: Generate a completion script
+    When I successfully run `turtle -t completions <shell>`
+    Then the stdout should contain "--complete"
+
+    Examples:
+      | shell |
+      | bash  |
+      | fish  |
+      | zsh   |
+
+  @turtle
+  Scenario: Complete targets
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      build foo: cp bar
+      build baz: cp foo
+
+      """
+    When I successfully run `turtle --complete targets`
+    Then the stdout should contain exactly:
+      """
+      baz
+      foo
+      """
+
+  @turtle
+  Scenario: Complete targets with a cache
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      build foo: cp bar
+
+      """
+    And I successfully run `turtle --complete targets`
+    And a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      build baz: cp bar
+
+      """
+    When I successfully run `turtle --complete targets`
+    Then the stdout should contain exactly "baz"
+
+  @turtle
+  Scenario: Complete rules
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      """
+    When I successfully run `turtle --complete rules`
+    Then the stdout should contain exactly "cp"
@@ -22,3 +22,212 @@ Feature: Command line options
     When I run `turtle --log-prefix tomato`
     Then the exit status should not be 0
     And the stderr should contain "tomato"
+
+  Scenario: Build an output of a source file
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = echo $out && cp $in $out
+
+      build foo: cp foo.in
+      build bar: cp bar.in
+
+      """
+    And a file named "foo.in" with ""
+    And a file named "bar.in" with ""
+    When I successfully run `turtle foo.in^`
+    Then the stdout should contain exactly "foo"
+
+  @turtle
+  Scenario: Fail to build an output of a source file used by multiple builds
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      build foo: cp baz
+      build bar: cp baz
+
+      """
+    And a file named "baz" with ""
+    When I run `turtle baz^`
+    Then the exit status should not be 0
+    And the stderr should contain "bar, foo"
+
+  @turtle
+  Scenario: Build outputs matched by a glob pattern
+    Given a file named "build.ninja" with:
+      """
+      rule echo
+        command = echo $out
+
+      build out/foo.o: echo
+      build out/bar/baz.o: echo
+      build out/foo.a: echo
+
+      """
+    When I successfully run `turtle 'out/**/*.o'`
+    Then the stdout should contain "out/foo.o"
+    And the stdout should contain "out/bar/baz.o"
+    And the stdout should not contain "out/foo.a"
+
+  @turtle
+  Scenario: Build outputs in a directory
+    Given a file named "build.ninja" with:
+      """
+      rule echo
+        command = echo $out
+
+      build out/pkg/foo: echo
+      build out/bar: echo
+
+      """
+    When I successfully run `turtle out/pkg/`
+    Then the stdout should contain exactly "out/pkg/foo"
+
+  @turtle
+  Scenario: Build outputs of a rule
+    Given a file named "build.ninja" with:
+      """
+      rule foo
+        command = echo foo
+
+      rule bar
+        command = echo bar
+
+      build foo: foo
+      build bar: bar
+
+      """
+    When I successfully run `turtle --rule foo`
+    Then the stdout should contain exactly "foo"
+
+  @turtle
+  Scenario: Set a status format
+    Given a file named "build.ninja" with:
+      """
+      rule touch
+        command = touch $out
+        description = touch $out
+
+      build foo: touch
+      build bar: touch foo
+
+      """
+    When I successfully run `env TURTLE_STATUS='[%s/%t] ' turtle bar`
+    Then the stderr should contain "[1/2] touch foo"
+    And the stderr should contain "[2/2] touch bar"
+
+  @turtle
+  Scenario: Show a summary
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      build foo: cp bar
+
+      """
+    And a file named "bar" with ""
+    When I successfully run `turtle --summary`
+    And I successfully run `touch bar`
+    And I successfully run `turtle --summary`
+    Then the stderr should contain "1 ran, 0 skipped by timestamp hash, 0 skipped by content hash, 0 failed"
+    And the stderr should contain "0 ran, 0 skipped by timestamp hash, 1 skipped by content hash, 0 failed"
+
+  @turtle
+  Scenario: Write a trace
+    Given a file named "build.ninja" with:
+      """
+      rule touch
+        command = touch $out
+        description = touch $out
+
+      build foo: touch
+
+      """
+    When I successfully run `turtle --trace trace.json`
+    Then the file "trace.json" should contain "traceEvents"
+    And the file "trace.json" should contain "touch foo"
+
+  @turtle
+  Scenario: Write JSON events
+    Given a file named "build.ninja" with:
+      """
+      rule touch
+        command = touch $out
+        description = touch $out
+
+      build foo: touch
+
+      """
+    When I successfully run `turtle --output-format json`
+    And I successfully run `turtle --output-format json`
+    Then the stdout should contain:
+      """
+      {"event":"started","outputs":["foo"],"source":null,"description":"touch foo"}
+      """
+    And the stdout should contain:
+      """
+      {"event":"skipped","outputs":["foo"],"source":null}
+      """
+
+  @turtle
+  Scenario: Write a JSON error
+    Given a file named "build.ninja" with:
+      """
+      rule touch
+        command = touch $out
+
+      build foo: touch
+
+      """
+    When I run `turtle --output-format json bar`
+    Then the exit status should not be 0
+    And the stdout should contain:
+      """
+      {"event":"error","kind":"OutputNotFound","message":"output \"bar\" not found"}
+      """
+
+  @turtle
+  Scenario: Write a JUnit report on failure
+    Given a file named "build.ninja" with:
+      """
+      rule fail
+        command = echo oops >&2 && exit 42
+        description = fail $out
+
+      build foo: fail
+
+      """
+    When I run `turtle --junit report.xml`
+    Then the exit status should not be 0
+    And the file "report.xml" should contain "<testcase name=\"fail foo\" classname=\"fail\""
+    And the file "report.xml" should contain "<failure message=\"exit status 42\">oops"
+
+  Scenario: Show commands of build jobs
+    Given a file named "build.ninja" with:
+      """
+      rule echo
+        command = echo hello && touch $out
+
+      build foo: echo
+
+      """
+    When I successfully run `turtle -v`
+    Then the output should contain "echo hello && touch foo"
+
+  @turtle
+  Scenario: Map outputs to sources in outputs of build jobs
+    Given a file named "build.ninja" with:
+      """
+      rule cc
+        command = echo $out: warning && touch $out
+
+      build foo.o: cc foo.c
+        srcdep = foo.c
+
+      """
+    And a file named "foo.c" with ""
+    When I successfully run `turtle --map-sources`
+    Then the stdout should contain exactly "foo.c: warning"
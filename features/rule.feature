@@ -142,3 +142,22 @@ Feature: Rule statement
       hello
       hello
       """
+
+  @turtle
+  Scenario: Replay outputs of a skipped job
+    Given a file named "build.ninja" with:
+      """
+      rule hello
+        command = echo hello && touch $out
+        replay = 1
+
+      build foo: hello
+
+      """
+    When I successfully run `turtle`
+    And I successfully run `turtle`
+    Then the stdout should contain exactly:
+      """
+      hello
+      hello
+      """
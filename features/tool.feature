@@ -28,3 +28,428 @@ Feature: Tool
     And I successfully run `turtle`
     When I successfully run `turtle -t cleandead`
     Then the file "foo" should exist
+
+  @turtle
+  Scenario: Query an output
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      build foo: cp bar | baz
+      build qux: cp foo
+
+      """
+    When I successfully run `turtle -t query foo`
+    Then the stdout should contain exactly:
+      """
+      foo:
+        input: cp
+          bar
+          | baz
+        outputs:
+          qux
+      """
+
+  @turtle
+  Scenario: Export a build graph
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      build foo: cp bar
+
+      """
+    When I successfully run `turtle -t graph foo`
+    Then the stdout should contain "digraph"
+    And the stdout should contain "label=\"foo\""
+
+  @turtle
+  Scenario: Export a compilation database
+    Given a file named "build.ninja" with:
+      """
+      rule cc
+        command = cc -c $in -o $out
+
+      build foo.o: cc foo.c
+
+      """
+    When I successfully run `turtle -t compdb cc`
+    Then the stdout should contain "\"command\": \"cc -c foo.c -o foo.o\""
+    And the stdout should contain "\"file\": \"foo.c\""
+    And the stdout should contain "\"output\": \"foo.o\""
+
+  @turtle
+  Scenario: List targets
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      build foo: cp bar
+      build baz: cp foo
+
+      """
+    When I successfully run `turtle -t targets all`
+    Then the stdout should contain exactly:
+      """
+      baz: cp
+      foo: cp
+      """
+
+  @turtle
+  Scenario: List targets of a rule
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      rule touch
+        command = touch $out
+
+      build foo: cp bar
+      build baz: touch
+
+      """
+    When I successfully run `turtle -t targets rule cp`
+    Then the stdout should contain exactly:
+      """
+      foo
+      """
+
+  @turtle
+  Scenario: List rules
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+        description = copy $out
+
+      rule touch
+        command = touch $out
+
+      """
+    When I successfully run `turtle -t rules`
+    Then the stdout should contain exactly:
+      """
+      cp: copy $out
+      touch
+      """
+
+  @turtle
+  Scenario: Print commands
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      build foo: cp bar
+      build baz: cp foo
+
+      """
+    When I successfully run `turtle -t commands baz`
+    Then the stdout should contain exactly:
+      """
+      cp bar foo
+      cp foo baz
+      """
+
+  @turtle
+  Scenario: Print a final command
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      build foo: cp bar
+      build baz: cp foo
+
+      """
+    When I successfully run `turtle -t commands --final-only baz`
+    Then the stdout should contain exactly:
+      """
+      cp foo baz
+      """
+
+  @turtle
+  Scenario: List inputs
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      build foo: cp bar | baz
+      build qux: cp foo
+
+      """
+    When I successfully run `turtle -t inputs qux`
+    Then the stdout should contain exactly:
+      """
+      bar
+      baz
+      """
+
+  @turtle
+  Scenario: List outputs
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      build foo: cp bar
+      build baz: cp foo
+      build qux: cp blah
+
+      """
+    When I successfully run `turtle -t outputs bar`
+    Then the stdout should contain exactly:
+      """
+      baz
+      foo
+      """
+
+  @turtle
+  Scenario: List affected outputs
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      build foo: cp bar
+      build baz: cp foo
+      build qux: cp blah
+
+      """
+    When I successfully run `turtle -t affected bar README.md`
+    Then the stdout should contain exactly:
+      """
+      baz
+      foo
+      """
+
+  @turtle
+  Scenario: List affected default outputs
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+
+      build foo: cp bar
+      build baz: cp foo
+
+      default baz
+
+      """
+    When I successfully run `turtle -t affected --default-only bar`
+    Then the stdout should contain exactly:
+      """
+      baz
+      """
+
+  @turtle
+  Scenario: Detect missing dependencies
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+      rule dd
+        command = echo ninja_dyndep_version = 1 > $out && echo build foo: dyndep '|' baz >> $out
+
+      build foo: cp bar || foo.dd
+        dyndep = foo.dd
+      build foo.dd: dd
+      build baz: cp blah
+
+      """
+    And a file named "bar" with ""
+    And a file named "blah" with ""
+    And I successfully run `turtle`
+    When I run `turtle -t missingdeps`
+    Then the exit status should not be 0
+    And the stdout should contain "missing dependency: foo uses baz (generated by baz)"
+
+  @turtle
+  Scenario: Detect no missing dependency
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+        command = cp $in $out
+      rule dd
+        command = echo ninja_dyndep_version = 1 > $out && echo build foo: dyndep '|' baz >> $out
+
+      build foo: cp bar || foo.dd baz
+        dyndep = foo.dd
+      build foo.dd: dd
+      build baz: cp blah
+
+      """
+    And a file named "bar" with ""
+    And a file named "blah" with ""
+    And I successfully run `turtle`
+    When I successfully run `turtle -t missingdeps`
+    Then the stdout should contain exactly ""
+
+  @turtle
+  Scenario: Format build files
+    Given a file named "build.ninja" with:
+      """
+      # foo
+      rule cp
+          command = cp $
+            $in $out
+      build foo: cp bar
+
+      """
+    When I successfully run `turtle -t fmt`
+    Then the file "build.ninja" should contain exactly:
+      """
+      # foo
+      rule cp
+        command = cp $in $out
+
+      build foo: cp bar
+
+      """
+
+  @turtle
+  Scenario: Format build files with comments
+    Given a file named "build.ninja" with:
+      """
+      rule cc # compile
+          # compiler
+          command = cc -c $in -o $out
+      build foo.o: cc foo.c # foo
+          # flags
+          cflags = -O2
+
+      """
+    When I successfully run `turtle -t fmt`
+    Then the file "build.ninja" should contain exactly:
+      """
+      rule cc # compile
+        # compiler
+        command = cc -c $in -o $out
+
+      build foo.o: cc foo.c # foo
+        # flags
+        cflags = -O2
+
+      """
+
+  @turtle
+  Scenario: Check formats of build files
+    Given a file named "build.ninja" with:
+      """
+      rule cp
+          command = cp $in $out
+
+      """
+    When I run `turtle -t fmt --check`
+    Then the exit status should not be 0
+    And the stdout should contain "build.ninja"
+
+  @turtle
+  Scenario: Lint build files
+    Given a file named "build.ninja" with:
+      """
+      rule cc
+        command = cc $cflgas -o $out $in
+      rule cp
+        command = cp $in $out
+      build foo: cc bar
+
+      """
+    When I run `turtle -t lint`
+    Then the exit status should not be 0
+    And the stdout should contain "build.ninja:1: undefined variable \"cflgas\" in rule \"cc\""
+    And the stdout should contain "build.ninja:3: rule \"cp\" is never used"
+
+  @turtle
+  Scenario: Show lint warnings in builds
+    Given a file named "build.ninja" with:
+      """
+      x = 42
+      rule cp
+        command = cp $in $out
+      build foo: cp bar
+
+      """
+    And a file named "bar" with ""
+    When I successfully run `turtle --lint`
+    Then the stderr should contain "warning: build.ninja:1: variable \"x\" is never used"
+
+  @turtle
+  Scenario: Print a log of a job
+    Given a file named "build.ninja" with:
+      """
+      rule echo
+        command = echo hello && touch $out
+
+      build foo: echo
+
+      """
+    And I successfully run `turtle`
+    When I successfully run `turtle -t log foo`
+    Then the stdout should contain exactly "hello"
+
+  @turtle
+  Scenario: Print a log of a job to stdout and stderr
+    Given a file named "build.ninja" with:
+      """
+      rule echo
+        command = echo hello && echo world >&2 && touch $out
+
+      build foo: echo
+
+      """
+    And I successfully run `turtle`
+    When I successfully run `turtle -t log foo`
+    Then the stdout should contain exactly "hello"
+    And the stderr should contain exactly "world"
+
+  @turtle
+  Scenario: Print no log of a job without output
+    Given a file named "build.ninja" with:
+      """
+      rule touch
+        command = touch $out
+
+      build foo: touch
+
+      """
+    And I successfully run `turtle`
+    When I successfully run `turtle -t log foo`
+    Then the stdout should contain exactly ""
+
+  @turtle
+  Scenario: Fail to print a log of a job never run
+    Given a file named "build.ninja" with:
+      """
+      rule touch
+        command = touch $out
+
+      build foo: touch
+
+      """
+    When I run `turtle -t log foo`
+    Then the exit status should not be 0
+    And the stderr should contain "no log found for foo"
+
+  @turtle
+  Scenario: Print logs of failed jobs
+    Given a file named "build.ninja" with:
+      """
+      rule fail
+        command = echo error && false
+
+      build foo: fail
+
+      """
+    And I run `turtle`
+    When I successfully run `turtle -t last-failures`
+    Then the stdout should contain exactly:
+      """
+      FAILED: foo
+      error
+      """
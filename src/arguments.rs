@@ -3,7 +3,7 @@ use clap::{Parser, ValueEnum};
 #[derive(Parser)]
 #[clap(about = "The Ninja build system clone written in Rust", version)]
 pub struct Arguments {
-    #[clap(help = "Specify outputs")]
+    #[clap(help = "Specify outputs, sources with ^, directories, or glob patterns")]
     pub outputs: Vec<String>,
     #[clap(short, help = "Set a root build file")]
     pub file: Option<String>,
@@ -15,16 +15,100 @@ pub struct Arguments {
     pub log_prefix: Option<String>,
     #[clap(long, help = "Show no message on failure of build jobs")]
     pub quiet: bool,
+    #[clap(
+        short,
+        long,
+        help = "Show commands of build jobs",
+        env = "TURTLE_VERBOSE"
+    )]
+    pub verbose: bool,
     #[clap(long, help = "Show debug logs", env = "TURTLE_DEBUG")]
     pub debug: bool,
     #[clap(long, help = "Show profile timings", env = "TURTLE_PROFILE")]
     pub profile: bool,
+    #[clap(long, help = "Show a summary of jobs", env = "TURTLE_SUMMARY")]
+    pub summary: bool,
+    #[clap(
+        long,
+        help = "Map outputs to sources in outputs of build jobs",
+        env = "TURTLE_MAP_SOURCES"
+    )]
+    pub map_sources: bool,
+    #[clap(long, help = "Set an output format", default_value = "text")]
+    pub output_format: OutputFormat,
+    #[clap(long, help = "Write a Chrome trace of a build to a file")]
+    pub trace: Option<String>,
+    #[clap(long, help = "Write a JUnit XML report of jobs to a file")]
+    pub junit: Option<String>,
+    #[clap(long, help = "Show lint warnings of build files", env = "TURTLE_LINT")]
+    pub lint: bool,
     #[clap(short, help = "Use a complementary tool")]
     pub tool: Option<Tool>,
+    #[clap(
+        long,
+        help = "Include dynamic inputs of the last build in tool outputs"
+    )]
+    pub dynamic_inputs: bool,
+    #[clap(long, help = "Show only final commands in tool outputs")]
+    pub final_only: bool,
+    #[clap(long, help = "Separate paths by NUL characters in tool outputs")]
+    pub print0: bool,
+    #[clap(long, help = "Filter tool outputs by default outputs")]
+    pub default_only: bool,
+    #[clap(long, help = "Filter outputs by a rule")]
+    pub rule: Option<String>,
+    #[clap(long, help = "Set a port of tool servers")]
+    pub port: Option<u16>,
+    #[clap(long, help = "Check if build files are formatted")]
+    pub check: bool,
+    // Used by completion scripts. Positional arguments are reserved for targets.
+    #[clap(long, hide = true)]
+    pub complete: Option<CompletionKind>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Fish,
+    Zsh,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Text,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum CompletionKind {
+    Rules,
+    Targets,
+    Tools,
 }
 
 #[derive(Clone, ValueEnum)]
 #[clap(rename_all = "lower")]
 pub enum Tool {
+    Affected,
+    Browse,
     CleanDead,
+    Commands,
+    Completions,
+    #[value(name = "compdb")]
+    CompilationDatabase,
+    #[value(name = "fmt")]
+    Format,
+    Graph,
+    Inputs,
+    #[value(name = "last-failures")]
+    LastFailures,
+    Lint,
+    Log,
+    Lsp,
+    #[value(name = "missingdeps")]
+    MissingDependencies,
+    Outputs,
+    Query,
+    Rules,
+    Targets,
 }
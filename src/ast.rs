@@ -1,4 +1,6 @@
 mod build;
+mod comment;
+mod comments;
 mod default_output;
 mod dynamic_build;
 mod dynamic_module;
@@ -10,6 +12,8 @@ mod submodule;
 mod variable_definition;
 
 pub use build::*;
+pub use comment::*;
+pub use comments::*;
 pub use default_output::*;
 pub use dynamic_build::*;
 pub use dynamic_module::*;
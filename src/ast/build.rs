@@ -1,4 +1,4 @@
-use super::VariableDefinition;
+use super::{Comments, VariableDefinition};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Build {
@@ -9,6 +9,7 @@ pub struct Build {
     implicit_inputs: Vec<String>,
     order_only_inputs: Vec<String>,
     variable_definitions: Vec<VariableDefinition>,
+    comments: Comments,
 }
 
 impl Build {
@@ -29,9 +30,14 @@ impl Build {
             implicit_inputs,
             order_only_inputs,
             variable_definitions,
+            comments: Comments::default(),
         }
     }
 
+    pub fn with_comments(self, comments: Comments) -> Self {
+        Self { comments, ..self }
+    }
+
     pub fn outputs(&self) -> &[String] {
         &self.outputs
     }
@@ -59,4 +65,8 @@ impl Build {
     pub fn variable_definitions(&self) -> &[VariableDefinition] {
         &self.variable_definitions
     }
+
+    pub fn comments(&self) -> &Comments {
+        &self.comments
+    }
 }
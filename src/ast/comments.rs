@@ -0,0 +1,24 @@
+use super::Comment;
+
+// Comments inside statements which are not statements by themselves
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Comments {
+    trailing: Option<Comment>,
+    body: Vec<(usize, Comment)>,
+}
+
+impl Comments {
+    pub fn new(trailing: Option<Comment>, body: Vec<(usize, Comment)>) -> Self {
+        Self { trailing, body }
+    }
+
+    // A comment at the end of a first line
+    pub fn trailing(&self) -> Option<&Comment> {
+        self.trailing.as_ref()
+    }
+
+    // Comment lines with indices of body lines following them
+    pub fn body(&self) -> &[(usize, Comment)] {
+        &self.body
+    }
+}
@@ -1,14 +1,28 @@
+use super::Comments;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DefaultOutput {
     outputs: Vec<String>,
+    comments: Comments,
 }
 
 impl DefaultOutput {
     pub fn new(outputs: Vec<String>) -> Self {
-        Self { outputs }
+        Self {
+            outputs,
+            comments: Comments::default(),
+        }
+    }
+
+    pub fn with_comments(self, comments: Comments) -> Self {
+        Self { comments, ..self }
     }
 
     pub fn outputs(&self) -> &[String] {
         &self.outputs
     }
+
+    pub fn comments(&self) -> &Comments {
+        &self.comments
+    }
 }
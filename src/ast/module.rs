@@ -1,16 +1,36 @@
 use super::Statement;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, Eq)]
 pub struct Module {
     statements: Vec<Statement>,
+    lines: Vec<usize>,
 }
 
 impl Module {
     pub fn new(statements: Vec<Statement>) -> Self {
-        Self { statements }
+        Self {
+            statements,
+            lines: vec![],
+        }
+    }
+
+    pub fn with_lines(self, lines: Vec<usize>) -> Self {
+        Self { lines, ..self }
     }
 
     pub fn statements(&self) -> &[Statement] {
         &self.statements
     }
+
+    // Line numbers are 1-based and available only for parsed modules.
+    pub fn line(&self, index: usize) -> Option<usize> {
+        self.lines.get(index).copied()
+    }
+}
+
+// Line numbers are not a part of syntax.
+impl PartialEq for Module {
+    fn eq(&self, other: &Self) -> bool {
+        self.statements == other.statements
+    }
 }
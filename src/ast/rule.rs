@@ -1,8 +1,12 @@
+use super::Comments;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Rule {
     name: String,
     command: String,
     description: Option<String>,
+    replay: Option<String>,
+    comments: Comments,
 }
 
 impl Rule {
@@ -10,14 +14,21 @@ impl Rule {
         name: impl Into<String>,
         command: impl Into<String>,
         description: Option<String>,
+        replay: Option<String>,
     ) -> Self {
         Self {
             name: name.into(),
             command: command.into(),
             description,
+            replay,
+            comments: Comments::default(),
         }
     }
 
+    pub fn with_comments(self, comments: Comments) -> Self {
+        Self { comments, ..self }
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -29,4 +40,12 @@ impl Rule {
     pub fn description(&self) -> Option<&str> {
         self.description.as_deref()
     }
+
+    pub fn replay(&self) -> Option<&str> {
+        self.replay.as_deref()
+    }
+
+    pub fn comments(&self) -> &Comments {
+        &self.comments
+    }
 }
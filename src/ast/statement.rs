@@ -1,8 +1,9 @@
-use super::{Build, DefaultOutput, Include, Rule, Submodule, VariableDefinition};
+use super::{Build, Comment, DefaultOutput, Include, Rule, Submodule, VariableDefinition};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Statement {
     Build(Build),
+    Comment(Comment),
     Default(DefaultOutput),
     Include(Include),
     Rule(Rule),
@@ -16,6 +17,12 @@ impl From<Build> for Statement {
     }
 }
 
+impl From<Comment> for Statement {
+    fn from(comment: Comment) -> Self {
+        Self::Comment(comment)
+    }
+}
+
 impl From<DefaultOutput> for Statement {
     fn from(default: DefaultOutput) -> Self {
         Self::Default(default)
@@ -1,12 +1,13 @@
 use crate::ir::{Build, DynamicConfiguration};
 use itertools::Itertools;
 use petgraph::{
-    Graph,
+    Direction, Graph,
     algo::{kosaraju_scc, toposort},
     graph::{DefaultIx, NodeIndex},
+    visit::{Dfs, Reversed, Walker},
 };
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     error::Error,
     fmt::{self, Display, Formatter},
     sync::Arc,
@@ -28,7 +29,12 @@ impl BuildGraph {
         };
 
         for (output, build) in outputs {
-            for input in build.inputs().iter().chain(build.order_only_inputs()) {
+            for input in build
+                .inputs()
+                .iter()
+                .chain(build.implicit_inputs())
+                .chain(build.order_only_inputs())
+            {
                 this.add_edge(output.clone(), input.clone());
             }
 
@@ -44,6 +50,11 @@ impl BuildGraph {
             }
         }
 
+        // Add outputs without any dependencies.
+        for output in outputs.keys() {
+            this.add_node(output);
+        }
+
         this
     }
 
@@ -68,17 +79,84 @@ impl BuildGraph {
         Ok(())
     }
 
+    // Inputs come before their dependents.
+    pub fn sort(&self) -> Result<Vec<Arc<str>>, BuildGraphError> {
+        self.validate()?;
+
+        Ok(toposort(&self.graph, None)
+            .unwrap_or_default()
+            .into_iter()
+            .rev()
+            .map(|node| self.graph[node].clone())
+            .collect())
+    }
+
     pub fn validate_dynamic(
         &mut self,
         configuration: &DynamicConfiguration,
     ) -> Result<(), BuildGraphError> {
+        self.insert_dynamic(configuration);
+
+        self.validate()
+    }
+
+    pub fn insert_dynamic(&mut self, configuration: &DynamicConfiguration) {
         for (output, build) in configuration.outputs() {
             for input in build.inputs() {
                 self.add_edge(self.primary_outputs[output].clone(), input.clone());
             }
         }
+    }
 
-        self.validate()
+    pub fn dependents(&self, input: &str) -> Vec<Arc<str>> {
+        self.nodes
+            .get(input)
+            .map(|&node| {
+                self.graph
+                    .neighbors_directed(node, Direction::Incoming)
+                    .map(|node| self.graph[node].clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn reachable<'a>(&self, outputs: impl IntoIterator<Item = &'a str>) -> HashSet<Arc<str>> {
+        let mut dfs = Dfs::empty(&self.graph);
+        let mut paths = HashSet::new();
+
+        for output in outputs {
+            if let Some(&node) = self.nodes.get(output) {
+                dfs.move_to(node);
+
+                while let Some(node) = dfs.next(&self.graph) {
+                    paths.insert(self.graph[node].clone());
+                }
+            }
+        }
+
+        paths
+    }
+
+    pub fn reverse_reachable<'a>(
+        &self,
+        inputs: impl IntoIterator<Item = &'a str>,
+    ) -> HashSet<Arc<str>> {
+        let graph = Reversed(&self.graph);
+        let mut dfs = Dfs::empty(graph);
+
+        for input in inputs {
+            if let Some(&node) = self.nodes.get(input) {
+                dfs.stack.push(node);
+            }
+        }
+
+        dfs.iter(graph)
+            .map(|node| self.graph[node].clone())
+            .collect()
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.nodes.contains_key(path)
     }
 
     fn add_edge(&mut self, output: Arc<str>, input: Arc<str>) {
@@ -139,9 +217,10 @@ mod tests {
         Build::new(
             outputs,
             vec![],
-            Rule::new("", None).into(),
+            Rule::new("", "", None, false).into(),
             inputs,
             vec![],
+            vec![],
             None,
         )
     }
@@ -190,7 +269,8 @@ mod tests {
                     Build::new(
                         vec!["foo".into()],
                         vec![],
-                        Rule::new("", None).into(),
+                        Rule::new("", "", None, false).into(),
+                        vec![],
                         vec![],
                         vec!["bar".into()],
                         None
@@ -228,7 +308,8 @@ mod tests {
                     Build::new(
                         vec!["foo".into()],
                         vec![],
-                        Rule::new("", None).into(),
+                        Rule::new("", "", None, false).into(),
+                        vec![],
                         vec![],
                         vec!["foo".into()],
                         None
@@ -323,6 +404,147 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_dependents() {
+        let graph = BuildGraph::new(
+            &[
+                (
+                    "foo".into(),
+                    explicit_build(vec!["foo".into()], vec!["bar".into()]).into(),
+                ),
+                (
+                    "bar".into(),
+                    explicit_build(vec!["bar".into()], vec![]).into(),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        assert_eq!(graph.dependents("foo"), Vec::<Arc<str>>::new());
+        assert_eq!(graph.dependents("bar"), vec!["foo".into()]);
+        assert_eq!(graph.dependents("baz"), Vec::<Arc<str>>::new());
+    }
+
+    #[test]
+    fn sort_paths() {
+        let graph = BuildGraph::new(
+            &[
+                (
+                    "foo".into(),
+                    explicit_build(vec!["foo".into()], vec!["bar".into()]).into(),
+                ),
+                (
+                    "bar".into(),
+                    explicit_build(vec!["bar".into()], vec!["baz".into()]).into(),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        assert_eq!(
+            graph.sort(),
+            Ok(vec!["baz".into(), "bar".into(), "foo".into()])
+        );
+    }
+
+    #[test]
+    fn sort_circular_paths() {
+        assert_eq!(
+            BuildGraph::new(
+                &[(
+                    "foo".into(),
+                    explicit_build(vec!["foo".into()], vec!["foo".into()]).into()
+                )]
+                .into_iter()
+                .collect()
+            )
+            .sort(),
+            Err(BuildGraphError::CircularDependency(vec!["foo".into()]))
+        );
+    }
+
+    #[test]
+    fn get_reachable_paths() {
+        let mut graph = BuildGraph::new(
+            &[
+                (
+                    "foo".into(),
+                    explicit_build(vec!["foo".into()], vec!["bar".into()]).into(),
+                ),
+                (
+                    "bar".into(),
+                    explicit_build(vec!["bar".into()], vec!["baz".into()]).into(),
+                ),
+                (
+                    "qux".into(),
+                    explicit_build(vec!["qux".into()], vec![]).into(),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        assert_eq!(
+            graph.reachable(["bar"]),
+            ["bar".into(), "baz".into()].into_iter().collect()
+        );
+        assert_eq!(
+            graph.reachable(["foo"]),
+            ["foo".into(), "bar".into(), "baz".into()]
+                .into_iter()
+                .collect()
+        );
+
+        graph.insert_dynamic(&DynamicConfiguration::new(
+            [("bar".into(), DynamicBuild::new(vec!["qux".into()]))]
+                .into_iter()
+                .collect(),
+        ));
+
+        assert_eq!(
+            graph.reachable(["bar"]),
+            ["bar".into(), "baz".into(), "qux".into()]
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn get_reverse_reachable_paths() {
+        let graph = BuildGraph::new(
+            &[
+                (
+                    "foo".into(),
+                    explicit_build(vec!["foo".into()], vec!["bar".into()]).into(),
+                ),
+                (
+                    "bar".into(),
+                    explicit_build(vec!["bar".into()], vec!["baz".into()]).into(),
+                ),
+                (
+                    "qux".into(),
+                    explicit_build(vec!["qux".into()], vec!["baz".into()]).into(),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        assert_eq!(
+            graph.reverse_reachable(["bar"]),
+            ["foo".into(), "bar".into()].into_iter().collect()
+        );
+        assert_eq!(
+            graph.reverse_reachable(["baz"]),
+            ["foo".into(), "bar".into(), "baz".into(), "qux".into()]
+                .into_iter()
+                .collect()
+        );
+        assert_eq!(graph.reverse_reachable(["blah"]), HashSet::new());
+    }
+
     #[test]
     fn validate_circular_build_with_dependency_from_secondary_to_primary() {
         let build = Arc::new(explicit_build(vec!["foo".into(), "bar".into()], vec![]));
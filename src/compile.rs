@@ -3,8 +3,8 @@ mod error;
 mod global_state;
 mod module_state;
 
-pub use self::error::CompileError;
-use self::{context::Context, global_state::GlobalState, module_state::ModuleState};
+pub use self::{context::Context, error::CompileError};
+use self::{global_state::GlobalState, module_state::ModuleState};
 use crate::{
     ast,
     ir::{Build, Configuration, DynamicBuild, DynamicConfiguration, Rule},
@@ -19,12 +19,12 @@ use std::{
 };
 use train_map::TrainMap;
 
-const PHONY_RULE: &str = "phony";
-const BUILD_DIRECTORY_VARIABLE: &str = "builddir";
-const DYNAMIC_MODULE_VARIABLE: &str = "dyndep";
-const SOURCE_VARIABLE_NAME: &str = "srcdep";
+pub const PHONY_RULE: &str = "phony";
+pub const BUILD_DIRECTORY_VARIABLE: &str = "builddir";
+pub const DYNAMIC_MODULE_VARIABLE: &str = "dyndep";
+pub const SOURCE_VARIABLE_NAME: &str = "srcdep";
 
-static VARIABLE_PATTERN: Lazy<Regex> =
+pub static VARIABLE_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"\$([[:alpha:]_][[:alnum:]_]*)").unwrap());
 
 // TODO Use a string pool for paths.
@@ -39,6 +39,7 @@ pub fn compile(
         outputs: Default::default(),
         default_outputs: Default::default(),
         source_map: Default::default(),
+        rules: Default::default(),
     };
     let mut module_state = ModuleState {
         rules: TrainMap::new(),
@@ -62,6 +63,7 @@ pub fn compile(
         global_state.outputs,
         default_outputs,
         global_state.source_map,
+        global_state.rules,
         module_state
             .variables
             .get(BUILD_DIRECTORY_VARIABLE)
@@ -116,15 +118,24 @@ fn compile_module<'a>(
                             .ok_or_else(|| CompileError::RuleNotFound(build.rule().into()))?;
 
                         Some(Rule::new(
+                            rule.name(),
                             interpolate_variables(rule.command(), &variables),
                             rule.description()
                                 .map(|description| interpolate_variables(description, &variables)),
+                            // Like Ninja's boolean bindings, any non-empty value enables it.
+                            rule.replay().is_some_and(|replay| {
+                                !interpolate_variables(replay, &variables).is_empty()
+                            }),
                         ))
                     },
                     build
                         .inputs()
                         .iter()
-                        .chain(build.implicit_inputs())
+                        .map(|string| string.as_str().into())
+                        .collect(),
+                    build
+                        .implicit_inputs()
+                        .iter()
                         .map(|string| string.as_str().into())
                         .collect(),
                     build
@@ -147,6 +158,7 @@ fn compile_module<'a>(
                         .extend(outputs().map(|output| (output.as_str().into(), source.clone())));
                 }
             }
+            ast::Statement::Comment(_) => {}
             ast::Statement::Default(default) => {
                 global_state.default_outputs.extend(
                     default
@@ -164,6 +176,9 @@ fn compile_module<'a>(
                 )?;
             }
             ast::Statement::Rule(rule) => {
+                global_state
+                    .rules
+                    .insert(rule.name().into(), rule.description().map(From::from));
                 module_state.rules.insert(rule.name(), rule.clone());
             }
             ast::Statement::Submodule(submodule) => {
@@ -206,7 +221,7 @@ pub fn compile_dynamic(module: &ast::DynamicModule) -> Result<DynamicConfigurati
     ))
 }
 
-fn resolve_dependency<'a>(
+pub fn resolve_dependency<'a>(
     context: &'a Context,
     module_path: &Path,
     submodule_path: &str,
@@ -219,6 +234,12 @@ fn resolve_dependency<'a>(
         .ok_or_else(|| CompileError::ModuleNotFound(submodule_path.into()))?)
 }
 
+pub fn variable_names(template: &str) -> impl Iterator<Item = &str> {
+    VARIABLE_PATTERN
+        .captures_iter(template)
+        .map(|captures| captures.get(1).unwrap().as_str())
+}
+
 fn interpolate_variables(template: &str, variables: &TrainMap<&str, Arc<str>>) -> String {
     VARIABLE_PATTERN
         .replace_all(template, |captures: &Captures| {
@@ -263,14 +284,20 @@ mod tests {
     }
 
     fn ir_explicit_build(outputs: Vec<Arc<str>>, rule: Rule, inputs: Vec<Arc<str>>) -> Build {
-        Build::new(outputs, vec![], rule.into(), inputs, vec![], None)
+        Build::new(outputs, vec![], rule.into(), inputs, vec![], vec![], None)
     }
 
     fn create_simple_configuration(
         outputs: HashMap<Arc<str>, Arc<Build>>,
         default_outputs: HashSet<Arc<str>>,
     ) -> Configuration {
-        Configuration::new(outputs, default_outputs, Default::default(), None)
+        let rules = outputs
+            .values()
+            .filter_map(|build| build.rule())
+            .map(|rule| (rule.name().into(), None))
+            .collect();
+
+        Configuration::new(outputs, default_outputs, Default::default(), rules, None)
     }
 
     #[test]
@@ -296,7 +323,7 @@ mod tests {
                     ROOT_MODULE_PATH.clone(),
                     ast::Module::new(vec![
                         ast::VariableDefinition::new("x", "42").into(),
-                        ast::Rule::new("foo", "$x", None).into(),
+                        ast::Rule::new("foo", "$x", None, None).into(),
                         ast_explicit_build(vec!["bar".into()], "foo", vec![], vec![]).into(),
                     ])
                 )]
@@ -309,7 +336,12 @@ mod tests {
             create_simple_configuration(
                 [(
                     "bar".into(),
-                    ir_explicit_build(vec!["bar".into()], Rule::new("42", None), vec![]).into()
+                    ir_explicit_build(
+                        vec!["bar".into()],
+                        Rule::new("foo", "42", None, false),
+                        vec![]
+                    )
+                    .into()
                 )]
                 .into_iter()
                 .collect(),
@@ -327,7 +359,7 @@ mod tests {
                     ast::Module::new(vec![
                         ast::VariableDefinition::new("x", "1").into(),
                         ast::VariableDefinition::new("y", "2").into(),
-                        ast::Rule::new("foo", "$x $y", None).into(),
+                        ast::Rule::new("foo", "$x $y", None, None).into(),
                         ast_explicit_build(vec!["bar".into()], "foo", vec![], vec![]).into(),
                     ])
                 )]
@@ -340,7 +372,12 @@ mod tests {
             create_simple_configuration(
                 [(
                     "bar".into(),
-                    ir_explicit_build(vec!["bar".into()], Rule::new("1 2", None), vec![]).into()
+                    ir_explicit_build(
+                        vec!["bar".into()],
+                        Rule::new("foo", "1 2", None, false),
+                        vec![]
+                    )
+                    .into()
                 )]
                 .into_iter()
                 .collect(),
@@ -357,7 +394,7 @@ mod tests {
                     ROOT_MODULE_PATH.clone(),
                     ast::Module::new(vec![
                         ast::VariableDefinition::new("x_y", "42").into(),
-                        ast::Rule::new("foo", "$x_y", None).into(),
+                        ast::Rule::new("foo", "$x_y", None, None).into(),
                         ast_explicit_build(vec!["bar".into()], "foo", vec![], vec![]).into(),
                     ])
                 )]
@@ -370,7 +407,12 @@ mod tests {
             create_simple_configuration(
                 [(
                     "bar".into(),
-                    ir_explicit_build(vec!["bar".into()], Rule::new("42", None), vec![]).into()
+                    ir_explicit_build(
+                        vec!["bar".into()],
+                        Rule::new("foo", "42", None, false),
+                        vec![]
+                    )
+                    .into()
                 )]
                 .into_iter()
                 .collect(),
@@ -386,7 +428,7 @@ mod tests {
                 &[(
                     ROOT_MODULE_PATH.clone(),
                     ast::Module::new(vec![
-                        ast::Rule::new("foo", "$$", None).into(),
+                        ast::Rule::new("foo", "$$", None, None).into(),
                         ast_explicit_build(vec!["bar".into()], "foo", vec![], vec![]).into()
                     ])
                 )]
@@ -399,7 +441,12 @@ mod tests {
             create_simple_configuration(
                 [(
                     "bar".into(),
-                    ir_explicit_build(vec!["bar".into()], Rule::new("$", None), vec![]).into()
+                    ir_explicit_build(
+                        vec!["bar".into()],
+                        Rule::new("foo", "$", None, false),
+                        vec![]
+                    )
+                    .into()
                 )]
                 .into_iter()
                 .collect(),
@@ -415,7 +462,7 @@ mod tests {
                 &[(
                     ROOT_MODULE_PATH.clone(),
                     ast::Module::new(vec![
-                        ast::Rule::new("foo", "$in", None).into(),
+                        ast::Rule::new("foo", "$in", None, None).into(),
                         ast_explicit_build(vec!["bar".into()], "foo", vec!["baz".into()], vec![])
                             .into(),
                     ])
@@ -431,7 +478,7 @@ mod tests {
                     "bar".into(),
                     ir_explicit_build(
                         vec!["bar".into()],
-                        Rule::new("baz", None),
+                        Rule::new("foo", "baz", None, false),
                         vec!["baz".into()]
                     )
                     .into()
@@ -450,7 +497,7 @@ mod tests {
                 &[(
                     ROOT_MODULE_PATH.clone(),
                     ast::Module::new(vec![
-                        ast::Rule::new("foo", "$in", None).into(),
+                        ast::Rule::new("foo", "$in", None, None).into(),
                         ast::Build::new(
                             vec!["bar".into()],
                             vec![],
@@ -472,10 +519,14 @@ mod tests {
             create_simple_configuration(
                 [(
                     "bar".into(),
-                    ir_explicit_build(
+                    Build::new(
                         vec!["bar".into()],
-                        Rule::new("baz", None),
-                        vec!["baz".into(), "blah".into()]
+                        vec![],
+                        Rule::new("foo", "baz", None, false).into(),
+                        vec!["baz".into()],
+                        vec!["blah".into()],
+                        vec![],
+                        None
                     )
                     .into()
                 )]
@@ -493,7 +544,7 @@ mod tests {
                 &[(
                     ROOT_MODULE_PATH.clone(),
                     ast::Module::new(vec![
-                        ast::Rule::new("foo", "$out", None).into(),
+                        ast::Rule::new("foo", "$out", None, None).into(),
                         ast_explicit_build(vec!["bar".into()], "foo", vec![], vec![]).into(),
                     ])
                 )]
@@ -506,7 +557,12 @@ mod tests {
             create_simple_configuration(
                 [(
                     "bar".into(),
-                    ir_explicit_build(vec!["bar".into()], Rule::new("bar", None), vec![]).into()
+                    ir_explicit_build(
+                        vec!["bar".into()],
+                        Rule::new("foo", "bar", None, false),
+                        vec![]
+                    )
+                    .into()
                 )]
                 .into_iter()
                 .collect(),
@@ -520,7 +576,8 @@ mod tests {
         let build = Arc::new(Build::new(
             vec!["bar".into()],
             vec!["baz".into()],
-            Rule::new("bar", None).into(),
+            Rule::new("foo", "bar", None, false).into(),
+            vec![],
             vec![],
             vec![],
             None,
@@ -531,7 +588,7 @@ mod tests {
                 &[(
                     ROOT_MODULE_PATH.clone(),
                     ast::Module::new(vec![
-                        ast::Rule::new("foo", "$out", None).into(),
+                        ast::Rule::new("foo", "$out", None, None).into(),
                         ast::Build::new(
                             vec!["bar".into()],
                             vec!["baz".into()],
@@ -566,7 +623,7 @@ mod tests {
                 &[(
                     ROOT_MODULE_PATH.clone(),
                     ast::Module::new(vec![
-                        ast::Rule::new("foo", "$in", None).into(),
+                        ast::Rule::new("foo", "$in", None, None).into(),
                         ast::Build::new(
                             vec!["bar".into()],
                             vec![],
@@ -591,7 +648,8 @@ mod tests {
                     Build::new(
                         vec!["bar".into()],
                         vec![],
-                        Some(Rule::new("", None)),
+                        Some(Rule::new("foo", "", None, false)),
+                        vec![],
                         vec![],
                         vec!["baz".into()],
                         None
@@ -612,7 +670,7 @@ mod tests {
                 &[(
                     ROOT_MODULE_PATH.clone(),
                     ast::Module::new(vec![
-                        ast::Rule::new("foo", "", None).into(),
+                        ast::Rule::new("foo", "", None, None).into(),
                         ast_explicit_build(vec!["bar".into()], "foo", vec![], vec![]).into(),
                         ast_explicit_build(vec!["baz".into()], "foo", vec![], vec![]).into()
                     ])
@@ -627,11 +685,21 @@ mod tests {
                 [
                     (
                         "bar".into(),
-                        ir_explicit_build(vec!["bar".into()], Rule::new("", None), vec![]).into()
+                        ir_explicit_build(
+                            vec!["bar".into()],
+                            Rule::new("foo", "", None, false),
+                            vec![]
+                        )
+                        .into()
                     ),
                     (
                         "baz".into(),
-                        ir_explicit_build(vec!["baz".into()], Rule::new("", None), vec![]).into()
+                        ir_explicit_build(
+                            vec!["baz".into()],
+                            Rule::new("foo", "", None, false),
+                            vec![]
+                        )
+                        .into()
                     )
                 ]
                 .into_iter()
@@ -648,7 +716,7 @@ mod tests {
                 &[(
                     ROOT_MODULE_PATH.clone(),
                     ast::Module::new(vec![
-                        ast::Rule::new("foo", "$x", None).into(),
+                        ast::Rule::new("foo", "$x", None, None).into(),
                         ast_explicit_build(
                             vec!["bar".into()],
                             "foo",
@@ -667,7 +735,12 @@ mod tests {
             create_simple_configuration(
                 [(
                     "bar".into(),
-                    ir_explicit_build(vec!["bar".into()], Rule::new("42", None), vec![]).into()
+                    ir_explicit_build(
+                        vec!["bar".into()],
+                        Rule::new("foo", "42", None, false),
+                        vec![]
+                    )
+                    .into()
                 )]
                 .into_iter()
                 .collect(),
@@ -683,7 +756,7 @@ mod tests {
                 &[(
                     ROOT_MODULE_PATH.clone(),
                     ast::Module::new(vec![
-                        ast::Rule::new("foo", "foo", None).into(),
+                        ast::Rule::new("foo", "foo", None, None).into(),
                         ast_explicit_build(
                             vec!["bar".into()],
                             "foo",
@@ -705,17 +778,47 @@ mod tests {
             Configuration::new(
                 [(
                     "bar".into(),
-                    ir_explicit_build(vec!["bar".into()], Rule::new("foo", None), vec![]).into()
+                    ir_explicit_build(
+                        vec!["bar".into()],
+                        Rule::new("foo", "foo", None, false),
+                        vec![]
+                    )
+                    .into()
                 )]
                 .into_iter()
                 .collect(),
                 ["bar".into()].into_iter().collect(),
                 [("bar".into(), "oh-my-src".into())].into_iter().collect(),
+                [("foo".into(), None)].into_iter().collect(),
                 None,
             )
         );
     }
 
+    #[test]
+    fn compile_rules() {
+        assert_eq!(
+            compile(
+                &[(
+                    ROOT_MODULE_PATH.clone(),
+                    ast::Module::new(vec![
+                        ast::Rule::new("foo", "", None, None).into(),
+                        ast::Rule::new("bar", "", Some("$out".into()), None).into(),
+                    ])
+                )]
+                .into_iter()
+                .collect(),
+                &DEFAULT_DEPENDENCIES,
+                &ROOT_MODULE_PATH
+            )
+            .unwrap()
+            .rules(),
+            &[("foo".into(), None), ("bar".into(), Some("$out".into()))]
+                .into_iter()
+                .collect()
+        );
+    }
+
     #[test]
     fn compile_phony_rule() {
         assert_eq!(
@@ -742,6 +845,7 @@ mod tests {
                         None,
                         vec!["bar".into()],
                         vec![],
+                        vec![],
                         None
                     )
                     .into()
@@ -771,6 +875,7 @@ mod tests {
                 Default::default(),
                 Default::default(),
                 Default::default(),
+                Default::default(),
                 Some("foo".into())
             )
         );
@@ -807,6 +912,7 @@ mod tests {
                         None,
                         vec![],
                         vec![],
+                        vec![],
                         Some("bar".into())
                     )
                     .into()
@@ -839,7 +945,7 @@ mod tests {
                         (
                             SUBMODULE_PATH.into(),
                             ast::Module::new(vec![
-                                ast::Rule::new("foo", "$x", None).into(),
+                                ast::Rule::new("foo", "$x", None, None).into(),
                                 ast_explicit_build(vec!["bar".into()], "foo", vec![], vec![])
                                     .into()
                             ])
@@ -861,7 +967,12 @@ mod tests {
                 create_simple_configuration(
                     [(
                         "bar".into(),
-                        ir_explicit_build(vec!["bar".into()], Rule::new("42", None), vec![]).into()
+                        ir_explicit_build(
+                            vec!["bar".into()],
+                            Rule::new("foo", "42", None, false),
+                            vec![]
+                        )
+                        .into()
                     )]
                     .into_iter()
                     .collect(),
@@ -881,7 +992,7 @@ mod tests {
                             ROOT_MODULE_PATH.clone(),
                             ast::Module::new(vec![
                                 ast::VariableDefinition::new("x", "42").into(),
-                                ast::Rule::new("foo", "$x", None).into(),
+                                ast::Rule::new("foo", "$x", None, None).into(),
                                 ast::Submodule::new(SUBMODULE_PATH).into(),
                             ])
                         ),
@@ -909,7 +1020,12 @@ mod tests {
                 create_simple_configuration(
                     [(
                         "bar".into(),
-                        ir_explicit_build(vec!["bar".into()], Rule::new("42", None), vec![]).into()
+                        ir_explicit_build(
+                            vec!["bar".into()],
+                            Rule::new("foo", "42", None, false),
+                            vec![]
+                        )
+                        .into()
                     )]
                     .into_iter()
                     .collect(),
@@ -929,7 +1045,7 @@ mod tests {
                             ROOT_MODULE_PATH.clone(),
                             ast::Module::new(vec![
                                 ast::VariableDefinition::new("x", "42").into(),
-                                ast::Rule::new("foo", "$x", None).into(),
+                                ast::Rule::new("foo", "$x", None, None).into(),
                                 ast::Submodule::new(SUBMODULE_PATH).into(),
                                 ast_explicit_build(vec!["bar".into()], "foo", vec![], vec![])
                                     .into(),
@@ -956,7 +1072,12 @@ mod tests {
                 create_simple_configuration(
                     [(
                         "bar".into(),
-                        ir_explicit_build(vec!["bar".into()], Rule::new("42", None), vec![]).into()
+                        ir_explicit_build(
+                            vec!["bar".into()],
+                            Rule::new("foo", "42", None, false),
+                            vec![]
+                        )
+                        .into()
                     )]
                     .into_iter()
                     .collect(),
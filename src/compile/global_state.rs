@@ -9,4 +9,5 @@ pub struct GlobalState {
     pub outputs: HashMap<Arc<str>, Arc<Build>>,
     pub default_outputs: HashSet<Arc<str>>,
     pub source_map: HashMap<Arc<str>, Arc<str>>,
+    pub rules: HashMap<Arc<str>, Option<Arc<str>>>,
 }
@@ -0,0 +1,56 @@
+mod cache;
+
+pub use self::cache::Cache;
+use crate::arguments::{Arguments, Shell};
+use clap::CommandFactory;
+
+const BASH_SCRIPT: &str = include_str!("completion/turtle.bash");
+const ZSH_SCRIPT: &str = include_str!("completion/turtle.zsh");
+const FISH_SCRIPT: &str = include_str!("completion/turtle.fish");
+
+pub fn script(shell: Shell) -> String {
+    match shell {
+        Shell::Bash => BASH_SCRIPT,
+        Shell::Fish => FISH_SCRIPT,
+        Shell::Zsh => ZSH_SCRIPT,
+    }
+    .replace("{options}", &options().join(" "))
+}
+
+fn options() -> Vec<String> {
+    Arguments::command()
+        .get_arguments()
+        .filter(|argument| !argument.is_hide_set())
+        .flat_map(|argument| {
+            argument
+                .get_short()
+                .map(|short| format!("-{short}"))
+                .into_iter()
+                .chain(argument.get_long().map(|long| format!("--{long}")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_scripts() {
+        for shell in [Shell::Bash, Shell::Fish, Shell::Zsh] {
+            let script = script(shell);
+
+            assert!(script.contains("--complete"));
+            assert!(!script.contains("{options}"));
+        }
+    }
+
+    #[test]
+    fn list_options() {
+        let options = options();
+
+        assert!(options.contains(&"-t".into()));
+        assert!(options.contains(&"--rule".into()));
+        assert!(!options.contains(&"--complete".into()));
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{context::Context, error::ApplicationError, ir::Configuration};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use tokio::fs::{create_dir_all, read, write};
+
+// Completion candidates cached with modified times of build files
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Cache {
+    version: String,
+    modules: Vec<(PathBuf, SystemTime)>,
+    targets: Vec<String>,
+    rules: Vec<String>,
+}
+
+impl Cache {
+    pub fn new(configuration: &Configuration, modules: Vec<(PathBuf, SystemTime)>) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").into(),
+            modules,
+            targets: configuration
+                .outputs()
+                .keys()
+                .chain(configuration.default_outputs())
+                .map(|output| output.to_string())
+                .sorted()
+                .dedup()
+                .collect(),
+            rules: configuration
+                .rules()
+                .keys()
+                .map(|rule| rule.to_string())
+                .sorted()
+                .collect(),
+        }
+    }
+
+    pub fn targets(&self) -> &[String] {
+        &self.targets
+    }
+
+    pub fn rules(&self) -> &[String] {
+        &self.rules
+    }
+
+    // A stale or broken cache is never read.
+    pub async fn read(context: &Context, path: &Path) -> Option<Self> {
+        let cache = serde_json::from_slice::<Self>(&read(path).await.ok()?).ok()?;
+
+        if cache.version != env!("CARGO_PKG_VERSION") {
+            return None;
+        }
+
+        for (path, time) in &cache.modules {
+            if context
+                .file_system()
+                .metadata(path)
+                .await
+                .ok()?
+                .modified_time()
+                != *time
+            {
+                return None;
+            }
+        }
+
+        Some(cache)
+    }
+
+    pub async fn write(&self, path: &Path) -> Result<(), ApplicationError> {
+        if let Some(directory) = path.parent() {
+            create_dir_all(directory).await?;
+        }
+
+        write(path, serde_json::to_vec(self)?).await?;
+
+        Ok(())
+    }
+}
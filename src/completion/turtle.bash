@@ -0,0 +1,38 @@
+_turtle() {
+  local current=${COMP_WORDS[COMP_CWORD]}
+  local previous=${COMP_WORDS[COMP_CWORD - 1]}
+  local arguments=()
+  local index
+
+  for ((index = 1; index < COMP_CWORD - 1; index++)); do
+    case ${COMP_WORDS[index]} in
+    -C | -f)
+      arguments+=("${COMP_WORDS[index]}" "${COMP_WORDS[index + 1]}")
+      ;;
+    esac
+  done
+
+  case $previous in
+  -C)
+    COMPREPLY=($(compgen -d -- "$current"))
+    ;;
+  -f)
+    COMPREPLY=($(compgen -f -- "$current"))
+    ;;
+  -t)
+    COMPREPLY=($(compgen -W "$(turtle "${arguments[@]}" --complete tools 2>/dev/null)" -- "$current"))
+    ;;
+  --rule)
+    COMPREPLY=($(compgen -W "$(turtle "${arguments[@]}" --complete rules 2>/dev/null)" -- "$current"))
+    ;;
+  *)
+    if [[ $current == -* ]]; then
+      COMPREPLY=($(compgen -W "{options}" -- "$current"))
+    else
+      COMPREPLY=($(compgen -W "$(turtle "${arguments[@]}" --complete targets 2>/dev/null)" -- "$current"))
+    fi
+    ;;
+  esac
+}
+
+complete -F _turtle turtle
@@ -0,0 +1,19 @@
+function __turtle_complete
+  set -l tokens (commandline -opc)
+  set -l arguments
+
+  for index in (seq 2 (math (count $tokens) - 1))
+    switch $tokens[$index]
+      case -C -f
+        set -a arguments $tokens[$index] $tokens[(math $index + 1)]
+    end
+  end
+
+  turtle $arguments --complete $argv 2>/dev/null
+end
+
+complete -c turtle -f -a '(__turtle_complete targets)'
+complete -c turtle -s C -x -a '(__fish_complete_directories)'
+complete -c turtle -s f -r -F
+complete -c turtle -s t -x -a '(__turtle_complete tools)'
+complete -c turtle -l rule -x -a '(__turtle_complete rules)'
@@ -1,4 +1,7 @@
-use crate::infrastructure::{CommandRunner, Console, Database, FileSystem};
+use crate::{
+    infrastructure::{CommandRunner, Console, Database, FileSystem},
+    trace::Trace,
+};
 use tokio::sync::Mutex;
 
 pub struct Context {
@@ -6,20 +9,23 @@ pub struct Context {
     console: Mutex<Box<dyn Console + Send + Sync>>,
     database: Box<dyn Database + Send + Sync>,
     file_system: Box<dyn FileSystem + Send + Sync>,
+    trace: Trace,
 }
 
 impl Context {
     pub fn new(
         command_runner: impl CommandRunner + Send + Sync + 'static,
-        console: impl Console + Send + Sync + 'static,
+        console: Box<dyn Console + Send + Sync>,
         database: impl Database + Send + Sync + 'static,
         file_system: impl FileSystem + Send + Sync + 'static,
+        trace: Trace,
     ) -> Self {
         Self {
             command_runner: Box::new(command_runner),
-            console: Mutex::new(Box::new(console)),
+            console: Mutex::new(console),
             file_system: Box::new(file_system),
             database: Box::new(database),
+            trace,
         }
     }
 
@@ -38,4 +44,8 @@ impl Context {
     pub fn file_system(&self) -> &(dyn FileSystem + Send + Sync) {
         &*self.file_system
     }
+
+    pub fn trace(&self) -> &Trace {
+        &self.trace
+    }
 }
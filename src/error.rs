@@ -2,6 +2,7 @@ use crate::{
     build_graph::BuildGraphError, compile::CompileError, ir::Build,
     module_dependency::ModuleDependencyError, parse::ParseError,
 };
+use serde_json::{Map, Value, json};
 use std::{
     error::Error,
     fmt::{self, Display, Formatter},
@@ -11,6 +12,7 @@ use tokio::{io, task::JoinError};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ApplicationError {
+    AmbiguousSource(String, Vec<Arc<str>>),
     Build,
     BuildGraph(BuildGraphError),
     Compile(CompileError),
@@ -19,18 +21,93 @@ pub enum ApplicationError {
     FileNotFound(String),
     InputNotBuilt(String),
     InputNotFound(String),
+    InvalidTargetPattern(String),
     ModuleDependency(ModuleDependencyError),
     Other(String),
     OutputNotFound(String),
     Parse(ParseError),
+    RuleOutputNotFound(String),
     Sled(sled::Error),
 }
 
+impl ApplicationError {
+    // A name of an error variant for machine-readable outputs
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::AmbiguousSource(..) => "AmbiguousSource",
+            Self::Build => "Build",
+            Self::BuildGraph(_) => "BuildGraph",
+            Self::Compile(_) => "Compile",
+            Self::DefaultOutputNotFound(_) => "DefaultOutputNotFound",
+            Self::DynamicDependencyNotFound(_) => "DynamicDependencyNotFound",
+            Self::FileNotFound(_) => "FileNotFound",
+            Self::InputNotBuilt(_) => "InputNotBuilt",
+            Self::InputNotFound(_) => "InputNotFound",
+            Self::InvalidTargetPattern(_) => "InvalidTargetPattern",
+            Self::ModuleDependency(_) => "ModuleDependency",
+            Self::Other(_) => "Other",
+            Self::OutputNotFound(_) => "OutputNotFound",
+            Self::Parse(_) => "Parse",
+            Self::RuleOutputNotFound(_) => "RuleOutputNotFound",
+            Self::Sled(_) => "Sled",
+        }
+    }
+
+    // Payloads of an error variant for machine-readable outputs
+    pub fn fields(&self) -> Map<String, Value> {
+        match self {
+            Self::AmbiguousSource(source, outputs) => {
+                vec![("source", json!(source)), ("outputs", strings(outputs))]
+            }
+            Self::BuildGraph(BuildGraphError::CircularDependency(cycle)) => {
+                vec![("cycle", strings(cycle))]
+            }
+            Self::Compile(CompileError::ModuleNotFound(path)) => vec![("path", json!(path))],
+            Self::Compile(CompileError::RuleNotFound(rule)) | Self::RuleOutputNotFound(rule) => {
+                vec![("rule", json!(rule))]
+            }
+            Self::DefaultOutputNotFound(output) => vec![("output", json!(output.as_ref()))],
+            Self::DynamicDependencyNotFound(build) => vec![
+                ("outputs", strings(build.outputs())),
+                (
+                    "path",
+                    json!(build.dynamic_module().map(AsRef::<str>::as_ref)),
+                ),
+            ],
+            Self::FileNotFound(path) => vec![("path", json!(path))],
+            Self::InputNotBuilt(input) | Self::InputNotFound(input) => {
+                vec![("input", json!(input))]
+            }
+            Self::InvalidTargetPattern(pattern) => vec![("pattern", json!(pattern))],
+            Self::OutputNotFound(output) => vec![("output", json!(output))],
+            Self::Parse(error) => vec![("line", json!(error.line()))],
+            Self::Build | Self::ModuleDependency(_) | Self::Other(_) | Self::Sled(_) => vec![],
+        }
+        .into_iter()
+        .map(|(key, value)| (key.into(), value))
+        .collect()
+    }
+}
+
+fn strings(strings: &[Arc<str>]) -> Value {
+    strings
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<&str>>()
+        .into()
+}
+
 impl Error for ApplicationError {}
 
 impl Display for ApplicationError {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         match self {
+            Self::AmbiguousSource(source, outputs) => write!(
+                formatter,
+                "source \"{}\" is used by multiple outputs: {}",
+                source,
+                outputs.join(", ")
+            ),
             Self::Build => write!(formatter, "build failed"),
             Self::Compile(error) => write!(formatter, "{error}"),
             Self::DefaultOutputNotFound(output) => {
@@ -51,6 +128,9 @@ impl Display for ApplicationError {
             Self::InputNotFound(input) => {
                 write!(formatter, "input \"{input}\" not found")
             }
+            Self::InvalidTargetPattern(pattern) => {
+                write!(formatter, "invalid target pattern \"{pattern}\"")
+            }
             Self::ModuleDependency(error) => {
                 write!(formatter, "{error}")
             }
@@ -59,6 +139,9 @@ impl Display for ApplicationError {
                 write!(formatter, "output \"{output}\" not found")
             }
             Self::Parse(error) => write!(formatter, "{error}"),
+            Self::RuleOutputNotFound(rule) => {
+                write!(formatter, "no output of rule \"{rule}\" found")
+            }
             Self::Sled(error) => write!(formatter, "{error}"),
             Self::BuildGraph(error) => write!(formatter, "{error}"),
         }
@@ -77,6 +160,12 @@ impl From<CompileError> for ApplicationError {
     }
 }
 
+impl From<fmt::Error> for ApplicationError {
+    fn from(error: fmt::Error) -> Self {
+        Self::Other(error.to_string())
+    }
+}
+
 impl From<io::Error> for ApplicationError {
     fn from(error: io::Error) -> Self {
         Self::Other(error.to_string())
@@ -101,6 +190,12 @@ impl From<ParseError> for ApplicationError {
     }
 }
 
+impl From<serde_json::Error> for ApplicationError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Other(error.to_string())
+    }
+}
+
 impl From<sled::Error> for ApplicationError {
     fn from(error: sled::Error) -> Self {
         Self::Sled(error)
@@ -1,39 +1,265 @@
+mod job;
+mod json;
+mod progress;
+mod status;
+
+pub use self::{job::Job, json::JsonConsole};
+use self::{progress::Progress, status::Status};
+use crate::ir::BuildId;
 use async_trait::async_trait;
-use std::{error::Error, fmt::Debug};
-use tokio::io::{AsyncWriteExt, Stderr, Stdout, stderr, stdout};
+use serde_json::{Map, Value};
+use std::{
+    error::Error,
+    io::{IsTerminal, stderr as std_stderr, stdout as std_stdout},
+    process::Output,
+    time::Duration,
+};
+use terminal_size::{Width, terminal_size_of};
+use tokio::{
+    io::{AsyncWriteExt, Stderr, Stdout, stderr, stdout},
+    time::Instant,
+};
+
+const DEFAULT_TERMINAL_WIDTH: usize = 80;
+// Placeholders of status formats depending on total numbers of jobs
+const TOTAL_PLACEHOLDERS: &[&str] = &["%t", "%u", "%p"];
 
 #[async_trait]
 pub trait Console {
     async fn write_stdout(&mut self, buffer: &[u8]) -> Result<(), Box<dyn Error>>;
     async fn write_stderr(&mut self, buffer: &[u8]) -> Result<(), Box<dyn Error>>;
+
+    // Errors have kinds and fields of their payloads for machine-readable
+    // outputs.
+    async fn write_error(
+        &mut self,
+        _kind: &str,
+        _fields: &Map<String, Value>,
+        message: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        self.write_stderr(format!("{message}\n").as_bytes()).await
+    }
+
+    // Reports of job statuses which consoles can ignore
+
+    // Returns `true` if builds estimated to run need to be accurate because
+    // consoles show their total numbers.
+    fn needs_total(&self) -> bool {
+        false
+    }
+
+    // Starts jobs with builds estimated to run.
+    async fn start_jobs(&mut self, _ids: &[BuildId]) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    async fn start_job(&mut self, _job: &Job<'_>) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    async fn finish_job(
+        &mut self,
+        _job: &Job<'_>,
+        output: &Output,
+        _duration: Duration,
+    ) -> Result<(), Box<dyn Error>> {
+        self.write_stdout(&output.stdout).await?;
+        self.write_stderr(&output.stderr).await
+    }
+
+    async fn skip_job(&mut self, _job: &Job<'_>) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    // Replays cached outputs of a skipped job.
+    async fn replay_job(
+        &mut self,
+        _job: &Job<'_>,
+        stdout: &[u8],
+        stderr: &[u8],
+    ) -> Result<(), Box<dyn Error>> {
+        self.write_stdout(stdout).await?;
+        self.write_stderr(stderr).await
+    }
+
+    async fn finish_jobs(&mut self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    async fn redraw(&mut self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
 pub struct OsConsole {
     stdout: Stdout,
     stderr: Stderr,
+    // A status area drawn at the bottom of stderr only if it is a terminal
+    status: Option<Status>,
+    stdout_terminal: bool,
+    progress: Progress,
+    // A format of prefixes of job descriptions
+    status_format: Option<String>,
 }
 
 impl OsConsole {
-    pub fn new() -> Self {
+    pub fn new(status_format: Option<String>, job_limit: usize) -> Self {
         Self {
             stdout: stdout(),
             stderr: stderr(),
+            status: std_stderr().is_terminal().then(Status::new),
+            stdout_terminal: std_stdout().is_terminal(),
+            progress: Progress::new(job_limit),
+            status_format,
+        }
+    }
+
+    async fn write_description(&mut self, description: &str) -> Result<(), Box<dyn Error>> {
+        let prefix = self
+            .status_format
+            .as_ref()
+            .map(|format| self.progress.format(format, Instant::now()))
+            .unwrap_or_default();
+
+        self.write_stderr(format!("{prefix}{description}\n").as_bytes())
+            .await
+    }
+
+    async fn write_job(&mut self, job: &Job<'_>) -> Result<(), Box<dyn Error>> {
+        if let Some(description) = job.description() {
+            self.write_description(description).await?;
+        }
+
+        if let Some(command) = job.command() {
+            self.write_stderr(format!("{command}\n").as_bytes()).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn clear_status(&mut self) -> Result<(), Box<dyn Error>> {
+        if let Some(status) = &mut self.status {
+            self.stderr.write_all(status.clear().as_bytes()).await?;
+            self.stderr.flush().await?;
         }
+
+        Ok(())
+    }
+
+    async fn draw_status(&mut self) -> Result<(), Box<dyn Error>> {
+        if let Some(status) = &mut self.status {
+            let width = terminal_size_of(std_stderr())
+                .map(|(Width(width), _)| width as usize)
+                .unwrap_or(DEFAULT_TERMINAL_WIDTH);
+
+            self.stderr
+                .write_all(
+                    status
+                        .draw(&self.progress, Instant::now(), width)
+                        .as_bytes(),
+                )
+                .await?;
+            self.stderr.flush().await?;
+        }
+
+        Ok(())
     }
 }
 
 #[async_trait]
 impl Console for OsConsole {
     async fn write_stdout(&mut self, src: &[u8]) -> Result<(), Box<dyn Error>> {
+        if !self.stdout_terminal {
+            self.stdout.write_all(src).await?;
+
+            return Ok(());
+        }
+
+        self.clear_status().await?;
         self.stdout.write_all(src).await?;
+        self.stdout.flush().await?;
 
-        Ok(())
+        if let Some(status) = &mut self.status {
+            status.write(src);
+        }
+
+        self.draw_status().await
     }
 
     async fn write_stderr(&mut self, src: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.clear_status().await?;
         self.stderr.write_all(src).await?;
 
+        if let Some(status) = &mut self.status {
+            status.write(src);
+        }
+
+        self.draw_status().await
+    }
+
+    fn needs_total(&self) -> bool {
+        self.status.is_some()
+            || self.status_format.as_ref().is_some_and(|format| {
+                TOTAL_PLACEHOLDERS
+                    .iter()
+                    .any(|placeholder| format.contains(placeholder))
+            })
+    }
+
+    async fn start_jobs(&mut self, ids: &[BuildId]) -> Result<(), Box<dyn Error>> {
+        self.progress.start_jobs(ids);
+
+        Ok(())
+    }
+
+    async fn start_job(&mut self, job: &Job<'_>) -> Result<(), Box<dyn Error>> {
+        self.progress.start_job(job.id());
+
+        if let Some(status) = &mut self.status {
+            status.start_job(job.id(), job.description().or(job.command()));
+            self.redraw().await
+        } else {
+            self.write_job(job).await
+        }
+    }
+
+    async fn finish_job(
+        &mut self,
+        job: &Job<'_>,
+        output: &Output,
+        _duration: Duration,
+    ) -> Result<(), Box<dyn Error>> {
+        self.progress.finish_job(Instant::now());
+
+        // Descriptions of finished jobs scroll up above a status area.
+        if let Some(status) = &mut self.status {
+            status.finish_job(job.id());
+            self.write_job(job).await?;
+        }
+
+        self.write_stdout(&output.stdout).await?;
+        self.write_stderr(&output.stderr).await
+    }
+
+    async fn skip_job(&mut self, job: &Job<'_>) -> Result<(), Box<dyn Error>> {
+        self.progress.skip_job(job.id());
+
+        Ok(())
+    }
+
+    async fn finish_jobs(&mut self) -> Result<(), Box<dyn Error>> {
+        self.clear_status().await?;
+
+        if let Some(status) = &mut self.status {
+            status.finish_jobs();
+        }
+
         Ok(())
     }
+
+    async fn redraw(&mut self) -> Result<(), Box<dyn Error>> {
+        self.clear_status().await?;
+        self.draw_status().await
+    }
 }
@@ -0,0 +1,51 @@
+use crate::ir::BuildId;
+use std::sync::Arc;
+
+// A job of a build reported to consoles
+#[derive(Clone, Copy, Debug)]
+pub struct Job<'a> {
+    id: BuildId,
+    outputs: &'a [Arc<str>],
+    source: Option<&'a str>,
+    description: Option<&'a str>,
+    // A command shown only in a verbose mode
+    command: Option<&'a str>,
+}
+
+impl<'a> Job<'a> {
+    pub fn new(
+        id: BuildId,
+        outputs: &'a [Arc<str>],
+        source: Option<&'a str>,
+        description: Option<&'a str>,
+        command: Option<&'a str>,
+    ) -> Self {
+        Self {
+            id,
+            outputs,
+            source,
+            description,
+            command,
+        }
+    }
+
+    pub fn id(&self) -> BuildId {
+        self.id
+    }
+
+    pub fn outputs(&self) -> &'a [Arc<str>] {
+        self.outputs
+    }
+
+    pub fn source(&self) -> Option<&'a str> {
+        self.source
+    }
+
+    pub fn description(&self) -> Option<&'a str> {
+        self.description
+    }
+
+    pub fn command(&self) -> Option<&'a str> {
+        self.command
+    }
+}
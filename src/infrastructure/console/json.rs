@@ -0,0 +1,254 @@
+use super::{Console, Job};
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::{borrow::Cow, error::Error, process::Output, time::Duration};
+use tokio::io::{AsyncWriteExt, Stdout, stdout};
+
+// A console writing newline-delimited JSON events to stdout
+#[derive(Debug)]
+pub struct JsonConsole {
+    stdout: Stdout,
+}
+
+impl JsonConsole {
+    pub fn new() -> Self {
+        Self { stdout: stdout() }
+    }
+
+    async fn write_event(&mut self, event: &Event<'_>) -> Result<(), Box<dyn Error>> {
+        let mut line = serde_json::to_vec(event)?;
+        line.push(b'\n');
+
+        self.stdout.write_all(&line).await?;
+        self.stdout.flush().await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Console for JsonConsole {
+    async fn write_stdout(&mut self, src: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.write_event(&Event::Message {
+            stream: Stream::Stdout,
+            text: String::from_utf8_lossy(src),
+        })
+        .await
+    }
+
+    async fn write_stderr(&mut self, src: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.write_event(&Event::Message {
+            stream: Stream::Stderr,
+            text: String::from_utf8_lossy(src),
+        })
+        .await
+    }
+
+    async fn write_error(
+        &mut self,
+        kind: &str,
+        fields: &Map<String, Value>,
+        message: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        self.write_event(&Event::Error {
+            kind,
+            fields,
+            message,
+        })
+        .await
+    }
+
+    async fn start_job(&mut self, job: &Job<'_>) -> Result<(), Box<dyn Error>> {
+        self.write_event(&Event::Started {
+            build: BuildFields::new(job),
+            description: job.description(),
+            command: job.command(),
+        })
+        .await
+    }
+
+    async fn finish_job(
+        &mut self,
+        job: &Job<'_>,
+        output: &Output,
+        duration: Duration,
+    ) -> Result<(), Box<dyn Error>> {
+        self.write_event(&Event::Finished {
+            build: BuildFields::new(job),
+            duration: duration.as_secs_f64(),
+            exit_status: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout),
+            stderr: String::from_utf8_lossy(&output.stderr),
+        })
+        .await
+    }
+
+    async fn skip_job(&mut self, job: &Job<'_>) -> Result<(), Box<dyn Error>> {
+        self.write_event(&Event::Skipped {
+            build: BuildFields::new(job),
+        })
+        .await
+    }
+
+    async fn replay_job(
+        &mut self,
+        job: &Job<'_>,
+        stdout: &[u8],
+        stderr: &[u8],
+    ) -> Result<(), Box<dyn Error>> {
+        self.write_event(&Event::Replayed {
+            build: BuildFields::new(job),
+            stdout: String::from_utf8_lossy(stdout),
+            stderr: String::from_utf8_lossy(stderr),
+        })
+        .await
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case", tag = "event")]
+enum Event<'a> {
+    Started {
+        #[serde(flatten)]
+        build: BuildFields<'a>,
+        description: Option<&'a str>,
+        // Only in a verbose mode
+        command: Option<&'a str>,
+    },
+    Finished {
+        #[serde(flatten)]
+        build: BuildFields<'a>,
+        // Seconds
+        duration: f64,
+        // `null` if a job is terminated by a signal
+        exit_status: Option<i32>,
+        stdout: Cow<'a, str>,
+        stderr: Cow<'a, str>,
+    },
+    Skipped {
+        #[serde(flatten)]
+        build: BuildFields<'a>,
+    },
+    // Cached outputs of a skipped job
+    Replayed {
+        #[serde(flatten)]
+        build: BuildFields<'a>,
+        stdout: Cow<'a, str>,
+        stderr: Cow<'a, str>,
+    },
+    Message {
+        stream: Stream,
+        text: Cow<'a, str>,
+    },
+    Error {
+        kind: &'a str,
+        #[serde(flatten)]
+        fields: &'a Map<String, Value>,
+        message: &'a str,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Serialize)]
+struct BuildFields<'a> {
+    outputs: Vec<&'a str>,
+    source: Option<&'a str>,
+}
+
+impl<'a> BuildFields<'a> {
+    fn new(job: &Job<'a>) -> Self {
+        Self {
+            outputs: job.outputs().iter().map(AsRef::as_ref).collect(),
+            source: job.source(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::ApplicationError, ir::BuildId};
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    #[test]
+    fn serialize_job_events() {
+        let outputs = [Arc::from("foo.o")];
+        let job = Job::new(
+            BuildId::new(0),
+            &outputs,
+            Some("foo.c"),
+            Some("cc foo"),
+            Some("cc -c foo.c"),
+        );
+
+        assert_eq!(
+            serde_json::to_string(&Event::Started {
+                build: BuildFields::new(&job),
+                description: job.description(),
+                command: job.command(),
+            })
+            .unwrap(),
+            r#"{"event":"started","outputs":["foo.o"],"source":"foo.c","description":"cc foo","command":"cc -c foo.c"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Event::Finished {
+                build: BuildFields::new(&job),
+                duration: 1.5,
+                exit_status: Some(1),
+                stdout: "".into(),
+                stderr: "error".into(),
+            })
+            .unwrap(),
+            r#"{"event":"finished","outputs":["foo.o"],"source":"foo.c","duration":1.5,"exit_status":1,"stdout":"","stderr":"error"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Event::Skipped {
+                build: BuildFields::new(&job),
+            })
+            .unwrap(),
+            r#"{"event":"skipped","outputs":["foo.o"],"source":"foo.c"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Event::Replayed {
+                build: BuildFields::new(&job),
+                stdout: "".into(),
+                stderr: "warning".into(),
+            })
+            .unwrap(),
+            r#"{"event":"replayed","outputs":["foo.o"],"source":"foo.c","stdout":"","stderr":"warning"}"#
+        );
+    }
+
+    #[test]
+    fn serialize_error_event() {
+        assert_eq!(
+            serde_json::to_string(&Event::Error {
+                kind: "OutputNotFound",
+                fields: &Default::default(),
+                message: "output \"foo\" not found",
+            })
+            .unwrap(),
+            r#"{"event":"error","kind":"OutputNotFound","message":"output \"foo\" not found"}"#
+        );
+
+        let error = ApplicationError::AmbiguousSource("foo.h".into(), vec!["bar.o".into()]);
+
+        assert_eq!(
+            serde_json::to_string(&Event::Error {
+                kind: error.kind(),
+                fields: &error.fields(),
+                message: &error.to_string(),
+            })
+            .unwrap(),
+            r#"{"event":"error","kind":"AmbiguousSource","outputs":["bar.o"],"source":"foo.h","message":"source \"foo.h\" is used by multiple outputs: bar.o"}"#
+        );
+    }
+}
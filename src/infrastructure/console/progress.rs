@@ -0,0 +1,199 @@
+use crate::ir::BuildId;
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt::Write,
+};
+use tokio::time::Instant;
+
+// Job counts of a build
+#[derive(Debug)]
+pub struct Progress {
+    // Jobs estimated to run and not started or skipped yet
+    pending: HashSet<BuildId>,
+    total: usize,
+    started: usize,
+    finished: usize,
+    start_time: Instant,
+    // Finish times of recent jobs for a current rate
+    finish_times: VecDeque<Instant>,
+    rate_window: usize,
+}
+
+impl Progress {
+    pub fn new(rate_window: usize) -> Self {
+        Self {
+            pending: HashSet::new(),
+            total: 0,
+            started: 0,
+            finished: 0,
+            start_time: Instant::now(),
+            finish_times: VecDeque::with_capacity(rate_window),
+            rate_window: rate_window.max(1),
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        // Jobs not estimated to run can be started.
+        self.total.max(self.started)
+    }
+
+    pub fn finished(&self) -> usize {
+        self.finished
+    }
+
+    pub fn running(&self) -> usize {
+        self.started - self.finished
+    }
+
+    pub fn start_jobs(&mut self, ids: &[BuildId]) {
+        *self = Self {
+            pending: ids.iter().copied().collect(),
+            total: ids.len(),
+            ..Self::new(self.rate_window)
+        };
+    }
+
+    pub fn start_job(&mut self, id: BuildId) {
+        self.pending.remove(&id);
+        self.started += 1;
+    }
+
+    pub fn finish_job(&mut self, now: Instant) {
+        self.finished += 1;
+
+        if self.finish_times.len() == self.rate_window {
+            self.finish_times.pop_front();
+        }
+
+        self.finish_times.push_back(now);
+    }
+
+    pub fn skip_job(&mut self, id: BuildId) {
+        if self.pending.remove(&id) {
+            self.total -= 1;
+        }
+    }
+
+    // Formats a status with placeholders compatible with `NINJA_STATUS`.
+    pub fn format(&self, format: &str, now: Instant) -> String {
+        let elapsed = (now - self.start_time).as_secs_f64();
+        let mut string = String::new();
+        let mut characters = format.chars();
+
+        while let Some(character) = characters.next() {
+            if character != '%' {
+                string.push(character);
+                continue;
+            }
+
+            match characters.next() {
+                Some('s') => write!(string, "{}", self.started),
+                Some('t') => write!(string, "{}", self.total()),
+                Some('r') => write!(string, "{}", self.running()),
+                Some('u') => write!(string, "{}", self.total() - self.started),
+                Some('f') => write!(string, "{}", self.finished),
+                Some('o') => write!(
+                    string,
+                    "{:.1}",
+                    if elapsed > 0.0 {
+                        self.finished as f64 / elapsed
+                    } else {
+                        0.0
+                    }
+                ),
+                Some('c') => match (self.finish_times.front(), self.finish_times.back()) {
+                    (Some(&first), Some(&last)) if last > first => write!(
+                        string,
+                        "{:.1}",
+                        self.finish_times.len() as f64 / (last - first).as_secs_f64()
+                    ),
+                    _ => write!(string, "?"),
+                },
+                Some('p') => write!(
+                    string,
+                    "{:3}%",
+                    (100 * self.finished)
+                        .checked_div(self.total())
+                        .unwrap_or(100)
+                ),
+                Some('e') => write!(string, "{elapsed:.3}"),
+                Some('%') => write!(string, "%"),
+                Some(character) => write!(string, "%{character}"),
+                None => write!(string, "%"),
+            }
+            .unwrap();
+        }
+
+        string
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn progress() -> Progress {
+        let mut progress = Progress::new(2);
+
+        progress.start_jobs(&(0..4).map(BuildId::new).collect::<Vec<_>>());
+
+        for id in 0..3 {
+            progress.start_job(BuildId::new(id));
+        }
+
+        progress.finish_job(Instant::now());
+
+        progress
+    }
+
+    #[test]
+    fn format_counts() {
+        assert_eq!(
+            progress().format("[%s/%f/%t/%r/%u] ", Instant::now()),
+            "[3/1/4/2/1] "
+        );
+    }
+
+    #[test]
+    fn format_percentage() {
+        assert_eq!(progress().format("%p", Instant::now()), " 25%");
+    }
+
+    #[test]
+    fn format_time() {
+        let progress = progress();
+        let now = progress.start_time + Duration::from_secs(2);
+
+        assert_eq!(progress.format("%e %o", now), "2.000 0.5");
+    }
+
+    #[test]
+    fn format_current_rate() {
+        let mut progress = progress();
+        let now = progress.start_time;
+
+        assert_eq!(progress.format("%c", now), "?");
+
+        for seconds in [2, 4, 8] {
+            progress.finish_job(now + Duration::from_secs(seconds));
+        }
+
+        assert_eq!(progress.format("%c", now), "0.5");
+    }
+
+    #[test]
+    fn format_literals() {
+        assert_eq!(progress().format("%% %x %", Instant::now()), "% %x %");
+    }
+
+    #[test]
+    fn skip_job() {
+        let mut progress = progress();
+
+        progress.skip_job(BuildId::new(3));
+        progress.skip_job(BuildId::new(42));
+
+        assert_eq!(progress.format("%f/%t", Instant::now()), "1/3");
+    }
+}
@@ -0,0 +1,198 @@
+use super::progress::Progress;
+use crate::ir::BuildId;
+use std::fmt::Write;
+use tokio::time::Instant;
+
+const MAX_JOB_LINES: usize = 8;
+
+#[derive(Debug)]
+struct Job {
+    id: BuildId,
+    description: Option<String>,
+    start_time: Instant,
+}
+
+// A status area of running jobs redrawn at the bottom of a terminal
+#[derive(Debug, Default)]
+pub struct Status {
+    jobs: Vec<Job>,
+    // Lines of a status area drawn currently
+    lines: usize,
+    // Whether the last output ends without a newline
+    open_line: bool,
+}
+
+impl Status {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start_job(&mut self, id: BuildId, description: Option<&str>) {
+        self.jobs.push(Job {
+            id,
+            description: description.map(From::from),
+            start_time: Instant::now(),
+        });
+    }
+
+    // Returns a description of a finished job.
+    pub fn finish_job(&mut self, id: BuildId) -> Option<String> {
+        let index = self.jobs.iter().position(|job| job.id == id)?;
+
+        self.jobs.remove(index).description
+    }
+
+    pub fn finish_jobs(&mut self) {
+        self.jobs.clear();
+    }
+
+    pub fn write(&mut self, buffer: &[u8]) {
+        if let Some(&byte) = buffer.last() {
+            self.open_line = byte != b'\n';
+        }
+    }
+
+    pub fn clear(&mut self) -> String {
+        if self.lines == 0 {
+            return String::new();
+        }
+
+        let lines = self.lines;
+        self.lines = 0;
+
+        format!("\x1b[{lines}A\x1b[J")
+    }
+
+    pub fn draw(&mut self, progress: &Progress, now: Instant, width: usize) -> String {
+        let lines = self.render(progress, now);
+
+        if lines.is_empty() {
+            return String::new();
+        }
+
+        let mut string = String::new();
+
+        if self.open_line {
+            string.push('\n');
+            self.open_line = false;
+        }
+
+        for line in &lines {
+            writeln!(
+                &mut string,
+                "{}",
+                line.chars()
+                    .take(width.saturating_sub(1))
+                    .collect::<String>()
+            )
+            .unwrap();
+        }
+
+        self.lines = lines.len();
+
+        string
+    }
+
+    fn render(&self, progress: &Progress, now: Instant) -> Vec<String> {
+        if self.jobs.is_empty() {
+            return vec![];
+        }
+
+        let described_jobs = self
+            .jobs
+            .iter()
+            .filter_map(|job| Some((job.description.as_deref()?, job.start_time)))
+            .collect::<Vec<_>>();
+
+        [format!(
+            "[{}/{}] running {} job{}",
+            progress.finished(),
+            progress.total(),
+            self.jobs.len(),
+            if self.jobs.len() == 1 { "" } else { "s" }
+        )]
+        .into_iter()
+        .chain(
+            described_jobs
+                .iter()
+                .take(MAX_JOB_LINES)
+                .map(|(description, start_time)| {
+                    format!(
+                        "  {description} ({:.1}s)",
+                        (now - *start_time).as_secs_f64()
+                    )
+                }),
+        )
+        .chain(
+            (described_jobs.len() > MAX_JOB_LINES)
+                .then(|| format!("  ... and {} more", described_jobs.len() - MAX_JOB_LINES)),
+        )
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn draw_nothing_without_jobs() {
+        let mut status = Status::new();
+
+        assert_eq!(status.draw(&Progress::new(1), Instant::now(), 80), "");
+        assert_eq!(status.clear(), "");
+    }
+
+    #[test]
+    fn draw_jobs() {
+        let mut status = Status::new();
+        let mut progress = Progress::new(1);
+        progress.start_jobs(&(0..3).map(BuildId::new).collect::<Vec<_>>());
+
+        for (id, description) in [(0, Some("foo")), (1, None)] {
+            progress.start_job(BuildId::new(id));
+            status.start_job(BuildId::new(id), description);
+        }
+
+        assert_eq!(
+            status.draw(&progress, Instant::now() + Duration::from_millis(1500), 80),
+            "[0/3] running 2 jobs\n  foo (1.5s)\n"
+        );
+        assert_eq!(status.clear(), "\x1b[2A\x1b[J");
+
+        progress.finish_job(Instant::now());
+
+        assert_eq!(status.finish_job(BuildId::new(0)), Some("foo".into()));
+        assert_eq!(
+            status.draw(&progress, Instant::now(), 80),
+            "[1/3] running 1 job\n"
+        );
+    }
+
+    #[test]
+    fn truncate_lines() {
+        let mut status = Status::new();
+        let mut progress = Progress::new(1);
+        progress.start_jobs(&[BuildId::new(0)]);
+        progress.start_job(BuildId::new(0));
+        status.start_job(BuildId::new(0), Some("foo bar baz"));
+
+        assert_eq!(
+            status.draw(&progress, Instant::now(), 10),
+            "[0/1] run\n  foo bar\n"
+        );
+    }
+
+    #[test]
+    fn close_open_line() {
+        let mut status = Status::new();
+        status.start_job(BuildId::new(0), None);
+        status.write(b"foo");
+
+        assert_eq!(
+            status.draw(&Progress::new(1), Instant::now(), 80),
+            "\n[0/0] running 1 job\n"
+        );
+    }
+}
@@ -1,12 +1,16 @@
 use crate::{hash_type::HashType, ir::BuildId};
 use async_trait::async_trait;
 use once_cell::sync::OnceCell;
-use std::{error::Error, path::Path, str, sync::LazyLock};
+use std::{error::Error, path::Path, str, sync::LazyLock, time::Duration};
 
 const TIMESTAMP_HASH_TREE_NAME: &str = "timestamp_hash";
 const CONTENT_HASH_TREE_NAME: &str = "content_hash";
 const OUTPUT_TREE_NAME: &str = "output";
 const SOURCE_TREE_NAME: &str = "source";
+const DYNAMIC_INPUT_TREE_NAME: &str = "dynamic_input";
+const DURATION_TREE_NAME: &str = "duration";
+const LOG_TREE_NAME: &str = "log";
+const FAILURE_TREE_NAME: &str = "failure";
 
 static BINCODE_CONFIGURATION: LazyLock<bincode::config::Configuration> = LazyLock::new(|| {
     bincode::config::Configuration::<
@@ -29,6 +33,21 @@ pub trait Database {
     fn get_source(&self, output: &str) -> Result<Option<String>, Box<dyn Error>>;
     fn set_source(&self, output: &str, source: &str) -> Result<(), Box<dyn Error>>;
 
+    fn get_dynamic_inputs(&self, id: BuildId) -> Result<Option<Vec<String>>, Box<dyn Error>>;
+    fn set_dynamic_inputs(&self, id: BuildId, inputs: &[&str]) -> Result<(), Box<dyn Error>>;
+
+    fn get_duration(&self, id: BuildId) -> Result<Option<Duration>, Box<dyn Error>>;
+    fn set_duration(&self, id: BuildId, duration: Duration) -> Result<(), Box<dyn Error>>;
+
+    // Paths of stdout and stderr logs
+    fn get_log(&self, id: BuildId) -> Result<Option<(String, String)>, Box<dyn Error>>;
+    fn set_log(&self, id: BuildId, stdout: &str, stderr: &str) -> Result<(), Box<dyn Error>>;
+    fn remove_log(&self, id: BuildId) -> Result<(), Box<dyn Error>>;
+
+    fn get_failures(&self) -> Result<Vec<BuildId>, Box<dyn Error>>;
+    fn set_failure(&self, id: BuildId) -> Result<(), Box<dyn Error>>;
+    fn clear_failures(&self) -> Result<(), Box<dyn Error>>;
+
     async fn flush(&self) -> Result<(), Box<dyn Error>>;
 }
 
@@ -62,6 +81,22 @@ impl OsDatabase {
     fn source_database(&self) -> Result<sled::Tree, Box<dyn Error>> {
         Ok(self.database()?.open_tree(SOURCE_TREE_NAME)?)
     }
+
+    fn dynamic_input_database(&self) -> Result<sled::Tree, Box<dyn Error>> {
+        Ok(self.database()?.open_tree(DYNAMIC_INPUT_TREE_NAME)?)
+    }
+
+    fn duration_database(&self) -> Result<sled::Tree, Box<dyn Error>> {
+        Ok(self.database()?.open_tree(DURATION_TREE_NAME)?)
+    }
+
+    fn log_database(&self) -> Result<sled::Tree, Box<dyn Error>> {
+        Ok(self.database()?.open_tree(LOG_TREE_NAME)?)
+    }
+
+    fn failure_database(&self) -> Result<sled::Tree, Box<dyn Error>> {
+        Ok(self.database()?.open_tree(FAILURE_TREE_NAME)?)
+    }
 }
 
 #[async_trait]
@@ -120,6 +155,89 @@ impl Database for OsDatabase {
         Ok(())
     }
 
+    fn get_dynamic_inputs(&self, id: BuildId) -> Result<Option<Vec<String>>, Box<dyn Error>> {
+        Ok(self
+            .dynamic_input_database()?
+            .get(id.to_bytes())?
+            .map(|value| {
+                bincode::decode_from_slice(&value, *BINCODE_CONFIGURATION).map(|(value, _)| value)
+            })
+            .transpose()?)
+    }
+
+    fn set_dynamic_inputs(&self, id: BuildId, inputs: &[&str]) -> Result<(), Box<dyn Error>> {
+        self.dynamic_input_database()?.insert(
+            id.to_bytes(),
+            bincode::encode_to_vec(inputs, *BINCODE_CONFIGURATION)?,
+        )?;
+
+        Ok(())
+    }
+
+    fn get_duration(&self, id: BuildId) -> Result<Option<Duration>, Box<dyn Error>> {
+        Ok(self
+            .duration_database()?
+            .get(id.to_bytes())?
+            .map(|value| {
+                bincode::decode_from_slice(&value, *BINCODE_CONFIGURATION).map(|(value, _)| value)
+            })
+            .transpose()?)
+    }
+
+    fn set_duration(&self, id: BuildId, duration: Duration) -> Result<(), Box<dyn Error>> {
+        self.duration_database()?.insert(
+            id.to_bytes(),
+            bincode::encode_to_vec(duration, *BINCODE_CONFIGURATION)?,
+        )?;
+
+        Ok(())
+    }
+
+    fn get_log(&self, id: BuildId) -> Result<Option<(String, String)>, Box<dyn Error>> {
+        Ok(self
+            .log_database()?
+            .get(id.to_bytes())?
+            .map(|value| {
+                bincode::decode_from_slice(&value, *BINCODE_CONFIGURATION).map(|(value, _)| value)
+            })
+            .transpose()?)
+    }
+
+    fn set_log(&self, id: BuildId, stdout: &str, stderr: &str) -> Result<(), Box<dyn Error>> {
+        self.log_database()?.insert(
+            id.to_bytes(),
+            bincode::encode_to_vec((stdout, stderr), *BINCODE_CONFIGURATION)?,
+        )?;
+
+        Ok(())
+    }
+
+    fn remove_log(&self, id: BuildId) -> Result<(), Box<dyn Error>> {
+        self.log_database()?.remove(id.to_bytes())?;
+
+        Ok(())
+    }
+
+    fn get_failures(&self) -> Result<Vec<BuildId>, Box<dyn Error>> {
+        self.failure_database()?
+            .iter()
+            .keys()
+            .map(|key| Ok(BuildId::from_bytes(key?.as_ref().try_into()?)))
+            .collect::<Result<_, _>>()
+    }
+
+    fn set_failure(&self, id: BuildId) -> Result<(), Box<dyn Error>> {
+        self.failure_database()?.insert(id.to_bytes(), &[])?;
+
+        Ok(())
+    }
+
+    fn clear_failures(&self) -> Result<(), Box<dyn Error>> {
+        self.failure_database()?.clear()?;
+
+        Ok(())
+    }
+
     async fn flush(&self) -> Result<(), Box<dyn Error>> {
         let database = self.database()?;
         database.flush_async().await?;
@@ -227,4 +345,74 @@ mod tests {
 
         assert_eq!(database.get_source("foo").unwrap(), Some("bar".into()));
     }
+
+    #[test]
+    fn get_dynamic_inputs() {
+        let database = OsDatabase::new();
+        database.initialize(tempdir().unwrap().path()).unwrap();
+
+        database
+            .set_dynamic_inputs(BuildId::new(0), &["foo", "bar"])
+            .unwrap();
+
+        assert_eq!(
+            database.get_dynamic_inputs(BuildId::new(0)).unwrap(),
+            Some(vec!["foo".into(), "bar".into()])
+        );
+        assert_eq!(database.get_dynamic_inputs(BuildId::new(1)).unwrap(), None);
+    }
+
+    #[test]
+    fn get_duration() {
+        let database = OsDatabase::new();
+        database.initialize(tempdir().unwrap().path()).unwrap();
+
+        database
+            .set_duration(BuildId::new(0), Duration::from_millis(42))
+            .unwrap();
+
+        assert_eq!(
+            database.get_duration(BuildId::new(0)).unwrap(),
+            Some(Duration::from_millis(42))
+        );
+        assert_eq!(database.get_duration(BuildId::new(1)).unwrap(), None);
+    }
+
+    #[test]
+    fn log() {
+        let database = OsDatabase::new();
+        database.initialize(tempdir().unwrap().path()).unwrap();
+
+        database
+            .set_log(BuildId::new(0), "foo.stdout.log", "foo.stderr.log")
+            .unwrap();
+
+        assert_eq!(
+            database.get_log(BuildId::new(0)).unwrap(),
+            Some(("foo.stdout.log".into(), "foo.stderr.log".into()))
+        );
+        assert_eq!(database.get_log(BuildId::new(1)).unwrap(), None);
+
+        database.remove_log(BuildId::new(0)).unwrap();
+
+        assert_eq!(database.get_log(BuildId::new(0)).unwrap(), None);
+    }
+
+    #[test]
+    fn failures() {
+        let database = OsDatabase::new();
+        database.initialize(tempdir().unwrap().path()).unwrap();
+
+        database.set_failure(BuildId::new(0)).unwrap();
+        database.set_failure(BuildId::new(1)).unwrap();
+
+        assert_eq!(
+            database.get_failures().unwrap(),
+            [BuildId::new(0), BuildId::new(1)]
+        );
+
+        database.clear_failures().unwrap();
+
+        assert_eq!(database.get_failures().unwrap(), []);
+    }
 }
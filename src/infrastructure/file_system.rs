@@ -25,6 +25,9 @@ pub trait FileSystem {
         buffer: &mut String,
     ) -> Result<(), Box<dyn Error>>;
     async fn metadata(&self, path: &Path) -> Result<Metadata, Box<dyn Error>>;
+    async fn write_file(&self, path: &Path, buffer: &[u8]) -> Result<(), Box<dyn Error>>;
+    // Removes a file if it exists.
+    async fn remove_file(&self, path: &Path) -> Result<(), Box<dyn Error>>;
     async fn create_directory(&self, path: &Path) -> Result<(), Box<dyn Error>>;
     async fn canonicalize_path(&self, path: &Path) -> Result<PathBuf, Box<dyn Error>>;
 }
@@ -115,6 +118,31 @@ impl FileSystem for OsFileSystem {
             .try_into()?)
     }
 
+    async fn write_file(&self, path: &Path, buffer: &[u8]) -> Result<(), Box<dyn Error>> {
+        while !self.path_lock.insert(path.into()) {
+            yield_now().await;
+        }
+
+        let permit = self.semaphore.acquire().await?;
+        let result = fs::write(path, buffer)
+            .await
+            .map_err(|error| Self::error(error, path).into());
+        drop(permit);
+
+        self.path_lock.remove(path);
+
+        result
+    }
+
+    async fn remove_file(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        match fs::remove_file(path).await {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                Err(Self::error(error, path).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
     async fn create_directory(&self, path: &Path) -> Result<(), Box<dyn Error>> {
         fs::create_dir_all(path)
             .await
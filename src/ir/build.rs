@@ -1,6 +1,7 @@
 use super::Rule;
 use std::{
     collections::hash_map::DefaultHasher,
+    fmt::{self, Display, Formatter},
     hash::{Hash, Hasher},
     sync::Arc,
 };
@@ -13,11 +14,21 @@ impl BuildId {
         Self(id)
     }
 
+    pub fn from_bytes(bytes: [u8; 8]) -> Self {
+        Self(u64::from_le_bytes(bytes))
+    }
+
     pub fn to_bytes(self) -> [u8; 8] {
         self.0.to_le_bytes()
     }
 }
 
+impl Display for BuildId {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{:016x}", self.0)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Build {
     // IDs are persistent across different builds so that they can be used for,
@@ -27,6 +38,7 @@ pub struct Build {
     implicit_outputs: Vec<Arc<str>>,
     rule: Option<Rule>,
     inputs: Vec<Arc<str>>,
+    implicit_inputs: Vec<Arc<str>>,
     order_only_inputs: Vec<Arc<str>>,
     dynamic_module: Option<Arc<str>>,
 }
@@ -37,6 +49,7 @@ impl Build {
         implicit_outputs: Vec<Arc<str>>,
         rule: Option<Rule>,
         inputs: Vec<Arc<str>>,
+        implicit_inputs: Vec<Arc<str>>,
         order_only_inputs: Vec<Arc<str>>,
         dynamic_module: Option<Arc<str>>,
     ) -> Self {
@@ -46,6 +59,7 @@ impl Build {
             implicit_outputs,
             rule,
             inputs,
+            implicit_inputs,
             order_only_inputs,
             dynamic_module,
         }
@@ -71,6 +85,10 @@ impl Build {
         &self.inputs
     }
 
+    pub fn implicit_inputs(&self) -> &[Arc<str>] {
+        &self.implicit_inputs
+    }
+
     pub fn order_only_inputs(&self) -> &[Arc<str>] {
         &self.order_only_inputs
     }
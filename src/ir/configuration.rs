@@ -9,6 +9,7 @@ pub struct Configuration {
     outputs: HashMap<Arc<str>, Arc<Build>>,
     default_outputs: HashSet<Arc<str>>,
     source_map: HashMap<Arc<str>, Arc<str>>,
+    rules: HashMap<Arc<str>, Option<Arc<str>>>,
     build_directory: Option<Arc<str>>,
 }
 
@@ -17,12 +18,14 @@ impl Configuration {
         outputs: HashMap<Arc<str>, Arc<Build>>,
         default_outputs: HashSet<Arc<str>>,
         source_map: HashMap<Arc<str>, Arc<str>>,
+        rules: HashMap<Arc<str>, Option<Arc<str>>>,
         build_directory: Option<Arc<str>>,
     ) -> Self {
         Self {
             outputs,
             default_outputs,
             source_map,
+            rules,
             build_directory,
         }
     }
@@ -39,6 +42,11 @@ impl Configuration {
         &self.source_map
     }
 
+    // Rule names and their description templates
+    pub fn rules(&self) -> &HashMap<Arc<str>, Option<Arc<str>>> {
+        &self.rules
+    }
+
     pub fn build_directory(&self) -> Option<&Arc<str>> {
         self.build_directory.as_ref()
     }
@@ -1,17 +1,30 @@
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Rule {
+    name: String,
     command: String,
     description: Option<String>,
+    replay: bool,
 }
 
 impl Rule {
-    pub fn new(command: impl Into<String>, description: Option<String>) -> Self {
+    pub fn new(
+        name: impl Into<String>,
+        command: impl Into<String>,
+        description: Option<String>,
+        replay: bool,
+    ) -> Self {
         Self {
+            name: name.into(),
             command: command.into(),
             description,
+            replay,
         }
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn command(&self) -> &str {
         &self.command
     }
@@ -19,4 +32,8 @@ impl Rule {
     pub fn description(&self) -> Option<&str> {
         self.description.as_deref()
     }
+
+    pub fn replay(&self) -> bool {
+        self.replay
+    }
 }
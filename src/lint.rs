@@ -0,0 +1,465 @@
+mod diagnostic;
+
+pub use self::diagnostic::Diagnostic;
+use crate::{
+    ast,
+    compile::{
+        BUILD_DIRECTORY_VARIABLE, CompileError, Context, DYNAMIC_MODULE_VARIABLE, PHONY_RULE,
+        SOURCE_VARIABLE_NAME, resolve_dependency, variable_names,
+    },
+    module_dependency::ModuleDependencyMap,
+};
+use itertools::Itertools;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+use train_map::TrainMap;
+
+const BUILT_IN_VARIABLES: &[&str] = &["in", "out"];
+// Variables used implicitly by builds or tools
+const SPECIAL_VARIABLES: &[&str] = &[
+    BUILD_DIRECTORY_VARIABLE,
+    DYNAMIC_MODULE_VARIABLE,
+    SOURCE_VARIABLE_NAME,
+    "ninja_required_version",
+];
+
+struct Definition<'a> {
+    path: &'a Path,
+    line: Option<usize>,
+    name: &'a str,
+    used: bool,
+}
+
+struct GlobalState<'a> {
+    directory: &'a Path,
+    rules: Vec<Definition<'a>>,
+    variables: Vec<Definition<'a>>,
+    outputs: HashMap<&'a str, (&'a Path, Option<usize>)>,
+    undefined_variables: HashSet<(usize, &'a str)>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+// Values are indices of definitions in a global state.
+struct ModuleState<'a, 'm> {
+    rules: TrainMap<'m, &'a str, (usize, &'a ast::Rule)>,
+    variables: TrainMap<'m, &'a str, usize>,
+}
+
+impl<'m> ModuleState<'_, 'm> {
+    fn fork(&'m self) -> Self {
+        Self {
+            rules: self.rules.fork(),
+            variables: self.variables.fork(),
+        }
+    }
+}
+
+pub fn lint(
+    modules: &HashMap<PathBuf, ast::Module>,
+    dependencies: &ModuleDependencyMap,
+    root_module_path: &Path,
+) -> Result<Vec<Diagnostic>, CompileError> {
+    let context = Context::new(modules, dependencies);
+    let mut global_state = GlobalState {
+        directory: root_module_path.parent().unwrap_or(root_module_path),
+        rules: vec![],
+        variables: vec![],
+        outputs: Default::default(),
+        undefined_variables: Default::default(),
+        diagnostics: vec![],
+    };
+
+    lint_module(
+        &context,
+        &mut global_state,
+        &mut ModuleState {
+            rules: TrainMap::new(),
+            variables: TrainMap::new(),
+        },
+        root_module_path,
+    )?;
+
+    for definition in &global_state.rules {
+        if !definition.used {
+            global_state.diagnostics.push(Diagnostic::new(
+                definition.path,
+                definition.line,
+                format!("rule \"{}\" is never used", definition.name),
+            ));
+        }
+    }
+
+    for definition in &global_state.variables {
+        if !definition.used && !SPECIAL_VARIABLES.contains(&definition.name) {
+            global_state.diagnostics.push(Diagnostic::new(
+                definition.path,
+                definition.line,
+                format!("variable \"{}\" is never used", definition.name),
+            ));
+        }
+    }
+
+    let mut diagnostics = global_state.diagnostics;
+    diagnostics.sort_by(|one, other| (one.path(), one.line()).cmp(&(other.path(), other.line())));
+
+    Ok(diagnostics)
+}
+
+fn lint_module<'a>(
+    context: &'a Context,
+    global_state: &mut GlobalState<'a>,
+    module_state: &mut ModuleState<'a, '_>,
+    path: &'a Path,
+) -> Result<(), CompileError> {
+    let module = context
+        .modules()
+        .get(path)
+        .ok_or_else(|| CompileError::ModuleNotFound(path.into()))?;
+
+    for (index, statement) in module.statements().iter().enumerate() {
+        let line = module.line(index);
+
+        match statement {
+            ast::Statement::Build(build) => {
+                let mut undefined_variables = vec![];
+
+                for value in build
+                    .outputs()
+                    .iter()
+                    .chain(build.implicit_outputs())
+                    .chain(build.inputs())
+                    .chain(build.implicit_inputs())
+                    .chain(build.order_only_inputs())
+                    .map(String::as_str)
+                    .chain(
+                        build
+                            .variable_definitions()
+                            .iter()
+                            .map(|definition| definition.value()),
+                    )
+                {
+                    for name in use_variables(global_state, module_state, value) {
+                        if !build
+                            .variable_definitions()
+                            .iter()
+                            .any(|definition| definition.name() == name)
+                            && !undefined_variables.contains(&name)
+                        {
+                            undefined_variables.push(name);
+                        }
+                    }
+                }
+
+                for name in undefined_variables {
+                    global_state.diagnostics.push(Diagnostic::new(
+                        path,
+                        line,
+                        format!(
+                            "undefined variable \"{}\" in build \"{}\"",
+                            name,
+                            build.outputs().join(" ")
+                        ),
+                    ));
+                }
+
+                if build.rule() == PHONY_RULE {
+                    if build.inputs().is_empty()
+                        && build.implicit_inputs().is_empty()
+                        && build.order_only_inputs().is_empty()
+                    {
+                        global_state.diagnostics.push(Diagnostic::new(
+                            path,
+                            line,
+                            format!(
+                                "phony build \"{}\" has no inputs",
+                                build.outputs().join(" ")
+                            ),
+                        ));
+                    }
+                } else if let Some(&(rule_index, rule)) = module_state.rules.get(build.rule()) {
+                    global_state.rules[rule_index].used = true;
+
+                    for name in variable_names(rule.command())
+                        .chain(rule.description().into_iter().flat_map(variable_names))
+                        .chain(rule.replay().into_iter().flat_map(variable_names))
+                    {
+                        if BUILT_IN_VARIABLES.contains(&name)
+                            || build
+                                .variable_definitions()
+                                .iter()
+                                .any(|definition| definition.name() == name)
+                        {
+                            continue;
+                        } else if let Some(&index) = module_state.variables.get(name) {
+                            global_state.variables[index].used = true;
+                        } else if global_state.undefined_variables.insert((rule_index, name)) {
+                            let definition = &global_state.rules[rule_index];
+
+                            global_state.diagnostics.push(Diagnostic::new(
+                                definition.path,
+                                definition.line,
+                                format!(
+                                    "undefined variable \"{}\" in rule \"{}\"",
+                                    name, definition.name
+                                ),
+                            ));
+                        }
+                    }
+                }
+
+                for output in build.outputs().iter().chain(build.implicit_outputs()) {
+                    if let Some((other_path, other_line)) =
+                        global_state.outputs.insert(output, (path, line))
+                    {
+                        global_state.diagnostics.push(Diagnostic::new(
+                            path,
+                            line,
+                            format!(
+                                "output \"{}\" shadows one defined at {}",
+                                output,
+                                format_location(
+                                    other_path
+                                        .strip_prefix(global_state.directory)
+                                        .unwrap_or(other_path),
+                                    other_line
+                                )
+                            ),
+                        ));
+                    }
+                }
+            }
+            ast::Statement::Comment(_) => {}
+            ast::Statement::Default(default) => {
+                for name in default
+                    .outputs()
+                    .iter()
+                    .flat_map(|output| use_variables(global_state, module_state, output))
+                    .unique()
+                    .collect::<Vec<_>>()
+                {
+                    global_state.diagnostics.push(Diagnostic::new(
+                        path,
+                        line,
+                        format!("undefined variable \"{name}\" in default outputs"),
+                    ));
+                }
+            }
+            ast::Statement::Include(include) => {
+                lint_module(
+                    context,
+                    global_state,
+                    module_state,
+                    resolve_dependency(context, path, include.path())?,
+                )?;
+            }
+            ast::Statement::Rule(rule) => {
+                module_state
+                    .rules
+                    .insert(rule.name(), (global_state.rules.len(), rule));
+                global_state.rules.push(Definition {
+                    path,
+                    line,
+                    name: rule.name(),
+                    used: false,
+                });
+            }
+            ast::Statement::Submodule(submodule) => {
+                lint_module(
+                    context,
+                    global_state,
+                    &mut module_state.fork(),
+                    resolve_dependency(context, path, submodule.path())?,
+                )?;
+            }
+            ast::Statement::VariableDefinition(definition) => {
+                for name in use_variables(global_state, module_state, definition.value())
+                    .into_iter()
+                    .unique()
+                {
+                    global_state.diagnostics.push(Diagnostic::new(
+                        path,
+                        line,
+                        format!("undefined variable \"{name}\""),
+                    ));
+                }
+
+                module_state
+                    .variables
+                    .insert(definition.name(), global_state.variables.len());
+                global_state.variables.push(Definition {
+                    path,
+                    line,
+                    name: definition.name(),
+                    used: false,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Marks variables in a value used and returns names of undefined ones.
+fn use_variables<'a>(
+    global_state: &mut GlobalState,
+    module_state: &ModuleState,
+    value: &'a str,
+) -> Vec<&'a str> {
+    variable_names(value)
+        .filter(|name| {
+            if let Some(&index) = module_state.variables.get(name) {
+                global_state.variables[index].used = true;
+                false
+            } else {
+                !BUILT_IN_VARIABLES.contains(name)
+            }
+        })
+        .collect()
+}
+
+fn format_location(path: &Path, line: Option<usize>) -> String {
+    let mut string = path.display().to_string();
+
+    if let Some(line) = line {
+        string.push_str(&format!(":{line}"));
+    }
+
+    string
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+    use pretty_assertions::assert_eq;
+
+    fn lint_source(source: &str) -> Vec<String> {
+        let path = PathBuf::from("build.ninja");
+
+        lint(
+            &[(path.clone(), parse(source).unwrap())]
+                .into_iter()
+                .collect(),
+            &[(path.clone(), Default::default())].into_iter().collect(),
+            &path,
+        )
+        .unwrap()
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+    }
+
+    #[test]
+    fn lint_nothing() {
+        assert_eq!(
+            lint_source("x = 1\nrule cp\n  command = cp $x $in $out\nbuild foo: cp bar\n"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn lint_undefined_variable() {
+        assert_eq!(
+            lint_source(
+                "rule cc\n  command = cc $cflgas $in\nbuild foo: cc bar\nbuild baz: cc qux\n"
+            ),
+            ["build.ninja:1: undefined variable \"cflgas\" in rule \"cc\""]
+        );
+    }
+
+    #[test]
+    fn lint_undefined_variable_in_variable_definition() {
+        assert_eq!(
+            lint_source("cflags = $cflgas -O2\nrule cc\n  command = cc $cflags\nbuild foo: cc\n"),
+            ["build.ninja:1: undefined variable \"cflgas\""]
+        );
+    }
+
+    #[test]
+    fn lint_undefined_variable_in_build() {
+        assert_eq!(
+            lint_source(
+                "rule cc\n  command = cc $extra\nbuild foo: cc bar\n  extra = $undefd $in $flags\n  flags = -O2\n"
+            ),
+            ["build.ninja:3: undefined variable \"undefd\" in build \"foo\""]
+        );
+    }
+
+    #[test]
+    fn lint_variables_in_build_paths() {
+        assert_eq!(
+            lint_source(
+                "root = src\nrule cp\n  command = cp $in $out\nbuild out/foo: cp $root/foo\nbuild all: phony out/$missing\n"
+            ),
+            ["build.ninja:5: undefined variable \"missing\" in build \"all\""]
+        );
+    }
+
+    #[test]
+    fn lint_variables_in_all_build_paths() {
+        assert_eq!(
+            lint_source(
+                "a = 1\nb = 2\nc = 3\nd = 4\nrule cp\n  command = cp\nbuild $a | $b: cp | $c || $d $x\n  x = 5\n"
+            ),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn lint_variables_in_default_outputs() {
+        assert_eq!(
+            lint_source("root = out\nbuild out/foo: phony bar\ndefault $root/foo $missing\n"),
+            ["build.ninja:3: undefined variable \"missing\" in default outputs"]
+        );
+    }
+
+    #[test]
+    fn lint_undefined_variable_in_replay() {
+        assert_eq!(
+            lint_source("rule cc\n  command = cc\n  replay = $replya\nbuild foo: cc\n"),
+            ["build.ninja:1: undefined variable \"replya\" in rule \"cc\""]
+        );
+    }
+
+    #[test]
+    fn lint_build_variable() {
+        assert_eq!(
+            lint_source("rule cc\n  command = cc $flags\nbuild foo: cc bar\n  flags = -O2\n"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn lint_unused_rule() {
+        assert_eq!(
+            lint_source("rule cc\n  command = cc\n"),
+            ["build.ninja:1: rule \"cc\" is never used"]
+        );
+    }
+
+    #[test]
+    fn lint_unused_variable() {
+        assert_eq!(
+            lint_source("x = 1\ny = $x\nbuilddir = foo\n"),
+            ["build.ninja:2: variable \"y\" is never used"]
+        );
+    }
+
+    #[test]
+    fn lint_phony_build_without_inputs() {
+        assert_eq!(
+            lint_source("build foo: phony\nbuild bar: phony foo\n"),
+            ["build.ninja:1: phony build \"foo\" has no inputs"]
+        );
+    }
+
+    #[test]
+    fn lint_shadowed_output() {
+        assert_eq!(
+            lint_source("build foo: phony bar\nbuild foo: phony baz\n"),
+            ["build.ninja:2: output \"foo\" shadows one defined at build.ninja:1"]
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    path: PathBuf,
+    line: Option<usize>,
+    message: String,
+}
+
+impl Diagnostic {
+    pub fn new(path: impl Into<PathBuf>, line: Option<usize>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            line,
+            message: message.into(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn relative_to(&self, directory: &Path) -> Self {
+        Self {
+            path: self
+                .path
+                .strip_prefix(directory)
+                .unwrap_or(&self.path)
+                .into(),
+            ..self.clone()
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.path.display())?;
+
+        if let Some(line) = self.line {
+            write!(formatter, ":{line}")?;
+        }
+
+        write!(formatter, ": {}", self.message)
+    }
+}
@@ -0,0 +1,316 @@
+mod analysis;
+mod index;
+mod source;
+
+use self::{analysis::Analysis, index::SymbolKind};
+use crate::{ast::Statement, compile::PHONY_RULE};
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{
+    fs::canonicalize,
+    io::{stdin, stdout},
+    sync::Mutex,
+};
+use tower_lsp::{
+    Client, LanguageServer, LspService, Server,
+    jsonrpc::Result,
+    lsp_types::{
+        CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams,
+        CompletionResponse, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+        DidOpenTextDocumentParams, DidSaveTextDocumentParams, GotoDefinitionParams,
+        GotoDefinitionResponse, Hover, HoverContents, HoverParams, HoverProviderCapability,
+        InitializeParams, InitializeResult, Location, MarkupContent, MarkupKind, OneOf, Position,
+        ReferenceParams, ServerCapabilities, ServerInfo, TextDocumentSyncCapability,
+        TextDocumentSyncKind, Url,
+    },
+};
+
+static RULE_COMPLETION_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[ \t]*build[^:]*:[ \t]*[[:alnum:]_]*$").unwrap());
+
+pub async fn serve(root_module_path: PathBuf) {
+    let (service, socket) = LspService::new(|client| Backend {
+        client,
+        root_module_path,
+        documents: Default::default(),
+        analyses: Default::default(),
+        published_paths: Default::default(),
+    });
+
+    Server::new(stdin(), stdout(), socket).serve(service).await;
+}
+
+struct Backend {
+    client: Client,
+    root_module_path: PathBuf,
+    documents: Mutex<HashMap<PathBuf, String>>,
+    // Analyses by root module paths
+    analyses: Mutex<HashMap<PathBuf, Arc<Analysis>>>,
+    published_paths: Mutex<HashMap<PathBuf, Vec<PathBuf>>>,
+}
+
+impl Backend {
+    async fn analysis(&self, path: &Path) -> Arc<Analysis> {
+        if let Some(analysis) = self
+            .analyses
+            .lock()
+            .await
+            .values()
+            .find(|analysis| analysis.contains(path))
+        {
+            return analysis.clone();
+        }
+
+        let documents = self.documents.lock().await.clone();
+        let mut analysis = Analysis::new(&self.root_module_path, &documents).await;
+
+        // Files out of a root module are analyzed on their own.
+        if !analysis.contains(path) {
+            analysis = Analysis::new(path, &documents).await;
+        }
+
+        let analysis = Arc::new(analysis);
+
+        self.analyses
+            .lock()
+            .await
+            .insert(analysis.root_module_path().into(), analysis.clone());
+
+        analysis
+    }
+
+    async fn update(&self, uri: &Url, text: Option<String>) {
+        let Some(path) = document_path(uri).await else {
+            return;
+        };
+
+        if let Some(text) = text {
+            self.documents.lock().await.insert(path.clone(), text);
+        } else {
+            self.documents.lock().await.remove(&path);
+        }
+
+        self.analyses.lock().await.clear();
+
+        let analysis = self.analysis(&path).await;
+        let paths = analysis.paths().map(Path::to_path_buf).collect::<Vec<_>>();
+
+        for path in self
+            .published_paths
+            .lock()
+            .await
+            .insert(analysis.root_module_path().into(), paths.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .chain(paths)
+            .unique()
+        {
+            if let Ok(uri) = Url::from_file_path(&path) {
+                self.client
+                    .publish_diagnostics(uri, analysis.diagnostics(&path).to_vec(), None)
+                    .await;
+            }
+        }
+    }
+
+    fn location(analysis: &Analysis, symbol: &index::Symbol) -> Option<Location> {
+        Some(Location::new(
+            Url::from_file_path(symbol.path()).ok()?,
+            analysis.source(symbol.path())?.range(symbol.range()),
+        ))
+    }
+}
+
+#[tower_lsp::async_trait]
+impl LanguageServer for Backend {
+    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+        Ok(InitializeResult {
+            capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                    TextDocumentSyncKind::FULL,
+                )),
+                definition_provider: Some(OneOf::Left(true)),
+                references_provider: Some(OneOf::Left(true)),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                completion_provider: Some(CompletionOptions::default()),
+                ..Default::default()
+            },
+            server_info: Some(ServerInfo {
+                name: env!("CARGO_BIN_NAME").into(),
+                version: Some(env!("CARGO_PKG_VERSION").into()),
+            }),
+        })
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        self.update(&params.text_document.uri, Some(params.text_document.text))
+            .await;
+    }
+
+    async fn did_change(&self, params: DidChangeTextDocumentParams) {
+        if let Some(change) = params.content_changes.into_iter().last() {
+            self.update(&params.text_document.uri, Some(change.text))
+                .await;
+        }
+    }
+
+    async fn did_save(&self, params: DidSaveTextDocumentParams) {
+        let text = self
+            .documents
+            .lock()
+            .await
+            .get(
+                &document_path(&params.text_document.uri)
+                    .await
+                    .unwrap_or_default(),
+            )
+            .cloned();
+
+        self.update(&params.text_document.uri, text).await;
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        self.update(&params.text_document.uri, None).await;
+    }
+
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>> {
+        let position = params.text_document_position_params;
+        let Some(path) = document_path(&position.text_document.uri).await else {
+            return Ok(None);
+        };
+        let analysis = self.analysis(&path).await;
+        let Some(source) = analysis.source(&path) else {
+            return Ok(None);
+        };
+
+        Ok(analysis
+            .index()
+            .definition(&path, source.offset(position.position))
+            .and_then(|symbol| Self::location(&analysis, symbol))
+            .map(GotoDefinitionResponse::Scalar))
+    }
+
+    async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let position = params.text_document_position;
+        let Some(path) = document_path(&position.text_document.uri).await else {
+            return Ok(None);
+        };
+        let analysis = self.analysis(&path).await;
+        let Some(source) = analysis.source(&path) else {
+            return Ok(None);
+        };
+        let offset = source.offset(position.position);
+
+        Ok(Some(
+            params
+                .context
+                .include_declaration
+                .then(|| analysis.index().definition(&path, offset))
+                .flatten()
+                .into_iter()
+                .chain(analysis.index().references(&path, offset))
+                .filter_map(|symbol| Self::location(&analysis, symbol))
+                .collect(),
+        ))
+    }
+
+    async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        let position = params.text_document_position_params;
+        let Some(path) = document_path(&position.text_document.uri).await else {
+            return Ok(None);
+        };
+        let analysis = self.analysis(&path).await;
+        let (Some(Statement::Build(build)), Some(configuration)) = (
+            analysis.statement(&path, position.position.line as usize + 1),
+            analysis.configuration(),
+        ) else {
+            return Ok(None);
+        };
+        let Some(rule) = build
+            .outputs()
+            .first()
+            .and_then(|output| configuration.outputs().get(output.as_str()))
+            .and_then(|build| build.rule())
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!(
+                    "```sh\n{}\n```{}",
+                    rule.command(),
+                    rule.description()
+                        .map(|description| format!("\n\n{description}"))
+                        .unwrap_or_default()
+                ),
+            }),
+            range: None,
+        }))
+    }
+
+    async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
+        let position = params.text_document_position;
+        let Some(path) = document_path(&position.text_document.uri).await else {
+            return Ok(None);
+        };
+        let analysis = self.analysis(&path).await;
+        let Some(source) = analysis.source(&path) else {
+            return Ok(None);
+        };
+        let prefix = &source.text()[source.offset(Position::new(position.position.line, 0))
+            ..source.offset(position.position)];
+
+        Ok(Some(CompletionResponse::Array(
+            if RULE_COMPLETION_PATTERN.is_match(prefix) {
+                analysis
+                    .index()
+                    .definitions()
+                    .iter()
+                    .filter(|symbol| symbol.kind() == SymbolKind::Rule)
+                    .map(|symbol| symbol.name())
+                    .chain([PHONY_RULE])
+                    .sorted()
+                    .dedup()
+                    .map(|name| CompletionItem {
+                        label: name.into(),
+                        kind: Some(CompletionItemKind::FUNCTION),
+                        ..Default::default()
+                    })
+                    .collect()
+            } else {
+                analysis
+                    .configuration()
+                    .into_iter()
+                    .flat_map(|configuration| configuration.outputs().keys())
+                    .sorted()
+                    .map(|output| CompletionItem {
+                        label: output.to_string(),
+                        kind: Some(CompletionItemKind::FILE),
+                        ..Default::default()
+                    })
+                    .collect()
+            },
+        )))
+    }
+}
+
+async fn document_path(uri: &Url) -> Option<PathBuf> {
+    let path = uri.to_file_path().ok()?;
+
+    Some(canonicalize(&path).await.unwrap_or(path))
+}
@@ -0,0 +1,202 @@
+use super::{index::Index, source::Source};
+use crate::{
+    ast::{Module, Statement},
+    compile::compile,
+    ir::Configuration,
+    lint::lint,
+    module_dependency::{self, ModuleDependencyMap},
+    parse::parse,
+};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+use tokio::fs::{canonicalize, read_to_string};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range};
+
+// An analysis of a module tree with sources of open documents preferred to
+// files.
+#[derive(Debug)]
+pub struct Analysis {
+    root_module_path: PathBuf,
+    sources: HashMap<PathBuf, Source>,
+    modules: HashMap<PathBuf, Module>,
+    configuration: Option<Configuration>,
+    diagnostics: HashMap<PathBuf, Vec<Diagnostic>>,
+    index: Index,
+}
+
+impl Analysis {
+    pub async fn new(root_module_path: &Path, documents: &HashMap<PathBuf, String>) -> Self {
+        let mut paths = vec![root_module_path.to_path_buf()];
+        let mut sources = HashMap::new();
+        let mut modules = HashMap::new();
+        let mut dependencies = ModuleDependencyMap::new();
+        let mut diagnostics = HashMap::<_, Vec<_>>::new();
+
+        while let Some(path) = paths.pop() {
+            if sources.contains_key(&path) {
+                continue;
+            }
+
+            let source = if let Some(source) = documents.get(&path) {
+                source.clone()
+            } else if let Ok(source) = read_to_string(&path).await {
+                source
+            } else {
+                continue;
+            };
+
+            match parse(&source) {
+                Ok(module) => {
+                    let mut submodule_paths = HashMap::new();
+
+                    for statement in module.statements() {
+                        let submodule_path = match statement {
+                            Statement::Include(include) => include.path(),
+                            Statement::Submodule(submodule) => submodule.path(),
+                            _ => continue,
+                        };
+                        let resolved_path = path.parent().unwrap().join(submodule_path);
+                        let resolved_path =
+                            canonicalize(&resolved_path).await.unwrap_or(resolved_path);
+
+                        paths.push(resolved_path.clone());
+                        submodule_paths.insert(submodule_path.to_owned(), resolved_path);
+                    }
+
+                    modules.insert(path.clone(), module);
+                    dependencies.insert(path.clone(), submodule_paths);
+                }
+                Err(error) => diagnostics
+                    .entry(path.clone())
+                    .or_default()
+                    .push(error_diagnostic(error.line(), error.to_string())),
+            }
+
+            sources.insert(path, Source::new(source));
+        }
+
+        let configuration = if diagnostics.is_empty() {
+            match module_dependency::validate(&dependencies)
+                .map_err(|error| error.to_string())
+                .and_then(|_| {
+                    compile(&modules, &dependencies, root_module_path)
+                        .map_err(|error| error.to_string())
+                }) {
+                Ok(configuration) => Some(configuration),
+                Err(message) => {
+                    diagnostics
+                        .entry(root_module_path.into())
+                        .or_default()
+                        .push(error_diagnostic(None, message));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        if configuration.is_some() {
+            for diagnostic in lint(&modules, &dependencies, root_module_path).unwrap_or_default() {
+                diagnostics
+                    .entry(diagnostic.path().into())
+                    .or_default()
+                    .push(Diagnostic {
+                        severity: Some(DiagnosticSeverity::WARNING),
+                        ..error_diagnostic(diagnostic.line(), diagnostic.message().into())
+                    });
+            }
+        }
+
+        let index = Index::new(&modules, &sources, &dependencies, root_module_path);
+
+        Self {
+            root_module_path: root_module_path.into(),
+            sources,
+            modules,
+            configuration,
+            diagnostics,
+            index,
+        }
+    }
+
+    pub fn root_module_path(&self) -> &Path {
+        &self.root_module_path
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.sources.contains_key(path)
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.sources.keys().map(PathBuf::as_path)
+    }
+
+    pub fn source(&self, path: &Path) -> Option<&Source> {
+        self.sources.get(path)
+    }
+
+    pub fn configuration(&self) -> Option<&Configuration> {
+        self.configuration.as_ref()
+    }
+
+    pub fn diagnostics(&self, path: &Path) -> &[Diagnostic] {
+        self.diagnostics
+            .get(path)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn index(&self) -> &Index {
+        &self.index
+    }
+
+    // A statement at a 1-based line
+    pub fn statement(&self, path: &Path, line: usize) -> Option<&Statement> {
+        let module = self.modules.get(path)?;
+
+        (0..module.statements().len())
+            .take_while(|&index| module.line(index).is_some_and(|start| start <= line))
+            .last()
+            .map(|index| &module.statements()[index])
+    }
+}
+
+fn error_diagnostic(line: Option<usize>, message: String) -> Diagnostic {
+    let position = Position::new(line.unwrap_or(1).saturating_sub(1) as u32, 0);
+
+    Diagnostic {
+        range: Range::new(position, Position::new(position.line + 1, 0)),
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some("turtle".into()),
+        message,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn analyze_circular_modules() {
+        let directory = tempdir().unwrap();
+        let root_module_path = directory.path().join("build.ninja");
+        let documents = [
+            (root_module_path.clone(), "include foo.ninja\n".into()),
+            (
+                directory.path().join("foo.ninja"),
+                "include build.ninja\n".into(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let analysis = Analysis::new(&root_module_path, &documents).await;
+
+        assert!(analysis.configuration().is_none());
+        assert_eq!(analysis.diagnostics(&root_module_path).len(), 1);
+    }
+}
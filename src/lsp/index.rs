@@ -0,0 +1,410 @@
+use super::source::Source;
+use crate::{
+    ast,
+    compile::{Context, VARIABLE_PATTERN, resolve_dependency},
+    module_dependency::ModuleDependencyMap,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+use train_map::TrainMap;
+
+static RULE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[ \t]*rule[ \t]+([[:alpha:]_][[:alnum:]_]*)").unwrap());
+static BUILD_RULE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[ \t]*build[^:]*:(?:[ \t]|\$\r?\n)*([[:alpha:]_][[:alnum:]_]*)").unwrap()
+});
+static VARIABLE_DEFINITION_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*([[:alpha:]_][[:alnum:]_]*)[ \t]*=").unwrap());
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SymbolKind {
+    Rule,
+    Variable,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Symbol {
+    kind: SymbolKind,
+    name: String,
+    path: PathBuf,
+    range: Range<usize>,
+}
+
+impl Symbol {
+    pub fn kind(&self) -> SymbolKind {
+        self.kind
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // A range of byte offsets in a source
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Index {
+    definitions: Vec<Symbol>,
+    // References and indices of their definitions
+    references: Vec<(Symbol, Option<usize>)>,
+}
+
+impl Index {
+    pub fn new(
+        modules: &HashMap<PathBuf, ast::Module>,
+        sources: &HashMap<PathBuf, Source>,
+        dependencies: &ModuleDependencyMap,
+        root_module_path: &Path,
+    ) -> Self {
+        let context = Context::new(modules, dependencies);
+        let mut index = Self::default();
+
+        index.index_module(
+            &context,
+            sources,
+            &mut ModuleState {
+                rules: TrainMap::new(),
+                variables: TrainMap::new(),
+            },
+            &mut vec![],
+            root_module_path,
+        );
+
+        index
+    }
+
+    pub fn definitions(&self) -> &[Symbol] {
+        &self.definitions
+    }
+
+    pub fn definition(&self, path: &Path, offset: usize) -> Option<&Symbol> {
+        self.definition_index(path, offset)
+            .map(|index| &self.definitions[index])
+    }
+
+    pub fn references(&self, path: &Path, offset: usize) -> Vec<&Symbol> {
+        let Some(index) = self.definition_index(path, offset) else {
+            return vec![];
+        };
+
+        self.references
+            .iter()
+            .filter(|(_, definition)| *definition == Some(index))
+            .map(|(symbol, _)| symbol)
+            .collect()
+    }
+
+    fn definition_index(&self, path: &Path, offset: usize) -> Option<usize> {
+        let contains = |symbol: &Symbol| {
+            symbol.path == path && symbol.range.start <= offset && offset <= symbol.range.end
+        };
+
+        self.definitions.iter().position(contains).or_else(|| {
+            self.references
+                .iter()
+                .find(|(symbol, _)| contains(symbol))
+                .and_then(|(_, definition)| *definition)
+        })
+    }
+
+    fn index_module<'a>(
+        &mut self,
+        context: &'a Context,
+        sources: &HashMap<PathBuf, Source>,
+        module_state: &mut ModuleState<'a, '_>,
+        // Paths of modules being indexed to stop at circular dependencies
+        ancestors: &mut Vec<&'a Path>,
+        path: &'a Path,
+    ) {
+        let (Some(module), Some(source)) = (context.modules().get(path), sources.get(path)) else {
+            return;
+        };
+
+        if ancestors.contains(&path) {
+            return;
+        }
+
+        ancestors.push(path);
+
+        for (index, statement) in module.statements().iter().enumerate() {
+            let Some(line) = module.line(index) else {
+                continue;
+            };
+            let range = source.lines_range(line, module.line(index + 1));
+            let text = &source.text()[range.clone()];
+
+            match statement {
+                ast::Statement::Build(build) => {
+                    let mut variables = module_state.variables.fork();
+
+                    for captures in VARIABLE_DEFINITION_PATTERN
+                        .captures_iter(text)
+                        .filter(|captures| captures[0].starts_with([' ', '\t']))
+                    {
+                        let name = captures.get(1).unwrap();
+
+                        if let Some(definition) = build
+                            .variable_definitions()
+                            .iter()
+                            .find(|definition| definition.name() == name.as_str())
+                        {
+                            variables.insert(definition.name(), self.definitions.len());
+                            self.definitions.push(Symbol {
+                                kind: SymbolKind::Variable,
+                                name: name.as_str().into(),
+                                path: path.into(),
+                                range: range.start + name.start()..range.start + name.end(),
+                            });
+                        }
+                    }
+
+                    self.index_variable_references(&variables, path, range.start, text);
+
+                    if let Some(name) = BUILD_RULE_PATTERN
+                        .captures(text)
+                        .and_then(|captures| captures.get(1))
+                    {
+                        self.references.push((
+                            Symbol {
+                                kind: SymbolKind::Rule,
+                                name: name.as_str().into(),
+                                path: path.into(),
+                                range: range.start + name.start()..range.start + name.end(),
+                            },
+                            module_state.rules.get(name.as_str()).copied(),
+                        ));
+                    }
+                }
+                ast::Statement::Comment(_) | ast::Statement::Default(_) => {}
+                ast::Statement::Include(include) => {
+                    if let Ok(submodule_path) = resolve_dependency(context, path, include.path()) {
+                        self.index_module(
+                            context,
+                            sources,
+                            module_state,
+                            ancestors,
+                            submodule_path,
+                        );
+                    }
+                }
+                ast::Statement::Rule(rule) => {
+                    self.index_variable_references(
+                        &module_state.variables,
+                        path,
+                        range.start,
+                        text,
+                    );
+
+                    if let Some(name) = RULE_PATTERN
+                        .captures(text)
+                        .and_then(|captures| captures.get(1))
+                    {
+                        module_state
+                            .rules
+                            .insert(rule.name(), self.definitions.len());
+                        self.definitions.push(Symbol {
+                            kind: SymbolKind::Rule,
+                            name: name.as_str().into(),
+                            path: path.into(),
+                            range: range.start + name.start()..range.start + name.end(),
+                        });
+                    }
+                }
+                ast::Statement::Submodule(submodule) => {
+                    if let Ok(submodule_path) = resolve_dependency(context, path, submodule.path())
+                    {
+                        self.index_module(
+                            context,
+                            sources,
+                            &mut module_state.fork(),
+                            ancestors,
+                            submodule_path,
+                        );
+                    }
+                }
+                ast::Statement::VariableDefinition(definition) => {
+                    self.index_variable_references(
+                        &module_state.variables,
+                        path,
+                        range.start,
+                        text,
+                    );
+
+                    if let Some(name) = VARIABLE_DEFINITION_PATTERN
+                        .captures(text)
+                        .and_then(|captures| captures.get(1))
+                    {
+                        module_state
+                            .variables
+                            .insert(definition.name(), self.definitions.len());
+                        self.definitions.push(Symbol {
+                            kind: SymbolKind::Variable,
+                            name: name.as_str().into(),
+                            path: path.into(),
+                            range: range.start + name.start()..range.start + name.end(),
+                        });
+                    }
+                }
+            }
+        }
+
+        ancestors.pop();
+    }
+
+    fn index_variable_references(
+        &mut self,
+        variables: &TrainMap<&str, usize>,
+        path: &Path,
+        offset: usize,
+        text: &str,
+    ) {
+        for captures in VARIABLE_PATTERN.captures_iter(text) {
+            let name = captures.get(1).unwrap();
+
+            self.references.push((
+                Symbol {
+                    kind: SymbolKind::Variable,
+                    name: name.as_str().into(),
+                    path: path.into(),
+                    range: offset + name.start()..offset + name.end(),
+                },
+                variables.get(name.as_str()).copied(),
+            ));
+        }
+    }
+}
+
+// Values are indices of definitions.
+struct ModuleState<'a, 'm> {
+    rules: TrainMap<'m, &'a str, usize>,
+    variables: TrainMap<'m, &'a str, usize>,
+}
+
+impl<'m> ModuleState<'_, 'm> {
+    fn fork(&'m self) -> Self {
+        Self {
+            rules: self.rules.fork(),
+            variables: self.variables.fork(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+    use pretty_assertions::assert_eq;
+
+    fn index(sources: &[(&str, &str)]) -> Index {
+        let sources = sources
+            .iter()
+            .map(|(path, source)| (PathBuf::from(path), Source::new(source.to_string())))
+            .collect::<HashMap<_, _>>();
+        let modules = sources
+            .iter()
+            .map(|(path, source)| (path.clone(), parse(source.text()).unwrap()))
+            .collect();
+        let dependencies = sources
+            .keys()
+            .map(|path| {
+                (
+                    path.clone(),
+                    sources
+                        .keys()
+                        .map(|path| (path.display().to_string(), path.clone()))
+                        .collect(),
+                )
+            })
+            .collect();
+
+        Index::new(&modules, &sources, &dependencies, Path::new("build.ninja"))
+    }
+
+    #[test]
+    fn find_rule_definition() {
+        let index = index(&[(
+            "build.ninja",
+            "rule cp\n  command = cp $in $out\nbuild foo: cp bar\n",
+        )]);
+
+        let definition = index.definition(Path::new("build.ninja"), 43).unwrap();
+
+        assert_eq!(definition.kind(), SymbolKind::Rule);
+        assert_eq!(definition.range(), 5..7);
+    }
+
+    #[test]
+    fn find_variable_definition() {
+        let index = index(&[(
+            "build.ninja",
+            "x = 1\nrule cp\n  command = cp $x\nbuild foo: cp\n  x = 2\nbuild bar: cp\n  y = $x\n",
+        )]);
+
+        assert_eq!(
+            index
+                .definition(Path::new("build.ninja"), 30)
+                .unwrap()
+                .range(),
+            0..1
+        );
+        assert_eq!(
+            index
+                .definition(Path::new("build.ninja"), 75)
+                .unwrap()
+                .range(),
+            0..1
+        );
+    }
+
+    #[test]
+    fn find_references_across_modules() {
+        let index = index(&[
+            (
+                "build.ninja",
+                "rule cp\n  command = cp\ninclude foo.ninja\n",
+            ),
+            ("foo.ninja", "build foo: cp bar\n"),
+        ]);
+
+        assert_eq!(
+            index
+                .references(Path::new("build.ninja"), 5)
+                .iter()
+                .map(|symbol| (symbol.path(), symbol.range()))
+                .collect::<Vec<_>>(),
+            [(Path::new("foo.ninja"), 11..13)]
+        );
+    }
+
+    #[test]
+    fn index_circular_modules() {
+        let index = index(&[
+            (
+                "build.ninja",
+                "rule cp\n  command = cp\ninclude foo.ninja\n",
+            ),
+            ("foo.ninja", "build foo: cp bar\ninclude build.ninja\n"),
+        ]);
+
+        assert_eq!(
+            index
+                .references(Path::new("build.ninja"), 5)
+                .iter()
+                .map(|symbol| (symbol.path(), symbol.range()))
+                .collect::<Vec<_>>(),
+            [(Path::new("foo.ninja"), 11..13)]
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use std::ops::Range;
+use tower_lsp::lsp_types::{Position, Range as LspRange};
+
+// A source text with positions in UTF-16 code units as in LSP.
+#[derive(Clone, Debug)]
+pub struct Source {
+    text: String,
+    line_starts: Vec<usize>,
+}
+
+impl Source {
+    pub fn new(text: String) -> Self {
+        let line_starts = [0]
+            .into_iter()
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        Self { text, line_starts }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn line(&self, line: usize) -> &str {
+        self.text[self.line_range(line)].trim_end_matches(['\r', '\n'])
+    }
+
+    // Offsets of lines between 1-based line numbers
+    pub fn lines_range(&self, start: usize, end: Option<usize>) -> Range<usize> {
+        self.line_start(start)
+            ..end
+                .map(|end| self.line_start(end))
+                .unwrap_or(self.text.len())
+    }
+
+    pub fn position(&self, offset: usize) -> Position {
+        let line = self
+            .line_starts
+            .partition_point(|&start| start <= offset)
+            .saturating_sub(1);
+
+        Position::new(
+            line as u32,
+            self.text[self.line_starts[line]..offset]
+                .encode_utf16()
+                .count() as u32,
+        )
+    }
+
+    pub fn range(&self, range: Range<usize>) -> LspRange {
+        LspRange::new(self.position(range.start), self.position(range.end))
+    }
+
+    pub fn offset(&self, position: Position) -> usize {
+        let start = self.line_start(position.line as usize + 1);
+        let line = self.line(position.line as usize + 1);
+        let mut length = 0;
+
+        for (index, character) in line.char_indices() {
+            if length >= position.character as usize {
+                return start + index;
+            }
+
+            length += character.len_utf16();
+        }
+
+        start + line.len()
+    }
+
+    fn line_start(&self, line: usize) -> usize {
+        self.line_starts
+            .get(line.saturating_sub(1))
+            .copied()
+            .unwrap_or(self.text.len())
+    }
+
+    fn line_range(&self, line: usize) -> Range<usize> {
+        self.lines_range(line, Some(line + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_positions() {
+        let source = Source::new("foo\nbär baz\n".into());
+
+        assert_eq!(source.position(0), Position::new(0, 0));
+        assert_eq!(source.position(4), Position::new(1, 0));
+        assert_eq!(source.position(9), Position::new(1, 4));
+        assert_eq!(source.offset(Position::new(1, 4)), 9);
+        assert_eq!(source.offset(Position::new(1, 42)), 12);
+        assert_eq!(source.line(2), "bär baz");
+    }
+}
@@ -2,38 +2,50 @@ mod arguments;
 mod ast;
 mod build_graph;
 mod compile;
+mod completion;
 mod context;
 mod error;
 mod hash_type;
 mod infrastructure;
 mod ir;
+mod lint;
+mod lsp;
 mod module_dependency;
 mod parse;
+mod print;
 mod run;
 mod tool;
+mod trace;
 
-use arguments::{Arguments, Tool};
+use arguments::{Arguments, CompletionKind, OutputFormat, Shell, Tool};
 use ast::{Module, Statement};
-use clap::Parser;
-use compile::compile;
+use clap::{Parser, ValueEnum};
+use compile::{BUILD_DIRECTORY_VARIABLE, compile};
+use completion::Cache;
 use context::Context;
 use error::ApplicationError;
 use futures::future::try_join_all;
-use infrastructure::{OsCommandRunner, OsConsole, OsDatabase, OsFileSystem};
+use infrastructure::{JsonConsole, OsCommandRunner, OsConsole, OsDatabase, OsFileSystem};
+use lint::lint;
 use module_dependency::ModuleDependencyMap;
 use parse::parse;
 use std::{
     collections::HashMap,
-    env::set_current_dir,
+    env::{self, set_current_dir},
+    fs::canonicalize,
+    path::absolute,
     path::{Path, PathBuf},
     process::exit,
     sync::Arc,
     time::Duration,
 };
-use tokio::time::sleep;
+use tokio::{fs::write, time::sleep};
+use trace::Trace;
 
 const DEFAULT_BUILD_FILE: &str = "build.ninja";
 const DATABASE_DIRECTORY: &str = ".turtle";
+const LOG_DIRECTORY: &str = "log";
+const COMPLETION_CACHE_FILE: &str = "completion.json";
 const OPEN_FILE_LIMIT: usize = if cfg!(target_os = "macos") { 256 } else { 1024 };
 const DEFAULT_FILE_COUNT_PER_PROCESS: usize = 3; // stdin, stdout, and stderr
 
@@ -43,33 +55,54 @@ async fn main() {
     let job_limit = arguments.job_limit.unwrap_or_else(num_cpus::get);
     let context = Context::new(
         OsCommandRunner::new(job_limit),
-        OsConsole::new(),
+        match arguments.output_format {
+            OutputFormat::Json => Box::new(JsonConsole::new()),
+            OutputFormat::Text => Box::new(OsConsole::new(
+                env::var("TURTLE_STATUS")
+                    .or_else(|_| env::var("NINJA_STATUS"))
+                    .ok(),
+                job_limit,
+            )),
+        },
         OsDatabase::new(),
         OsFileSystem::new(
             OPEN_FILE_LIMIT
                 .saturating_sub(DEFAULT_FILE_COUNT_PER_PROCESS * (job_limit + 1))
                 .max(1),
         ),
+        Trace::new(arguments.trace.is_some()),
     )
     .into();
 
-    if let Err(error) = execute(&context, &arguments).await {
+    let mut result = execute(&context, &arguments).await;
+
+    // Traces are written even on build failures.
+    if let Some(path) = &arguments.trace {
+        result = result.and(
+            write(path, context.trace().to_json())
+                .await
+                .map_err(From::from),
+        );
+    }
+
+    if let Err(error) = result {
         if !arguments.quiet || !matches!(error, ApplicationError::Build) {
             context
                 .console()
                 .lock()
                 .await
-                .write_stderr(
-                    format!(
-                        "{}{}\n",
+                .write_error(
+                    error.kind(),
+                    &error.fields(),
+                    &format!(
+                        "{}{}",
                         if let Some(prefix) = &arguments.log_prefix {
                             prefix
                         } else {
                             ""
                         },
                         error
-                    )
-                    .as_bytes(),
+                    ),
                 )
                 .await
                 .unwrap();
@@ -87,6 +120,37 @@ async fn execute(context: &Arc<Context>, arguments: &Arguments) -> Result<(), Ap
         set_current_dir(directory)?;
     }
 
+    if let Some(kind) = arguments.complete {
+        return complete(context, arguments, kind).await;
+    }
+
+    match &arguments.tool {
+        Some(Tool::Completions) => {
+            let [shell] = arguments.outputs.as_slice() else {
+                return Err(ApplicationError::Other(
+                    "a shell must be specified (bash, fish, or zsh)".into(),
+                ));
+            };
+            let shell = Shell::from_str(shell, false)
+                .map_err(|_| ApplicationError::Other(format!("unknown shell: {shell}")))?;
+
+            return Ok(context
+                .console()
+                .lock()
+                .await
+                .write_stdout(completion::script(shell).as_bytes())
+                .await?);
+        }
+        Some(Tool::Lsp) => {
+            let path = absolute(arguments.file.as_deref().unwrap_or(DEFAULT_BUILD_FILE))?;
+
+            lsp::serve(canonicalize(&path).unwrap_or(path)).await;
+
+            return Ok(());
+        }
+        _ => {}
+    }
+
     let root_module_path = context
         .file_system()
         .canonicalize_path(
@@ -97,33 +161,141 @@ async fn execute(context: &Arc<Context>, arguments: &Arguments) -> Result<(), Ap
                 .as_ref(),
         )
         .await?;
+
+    // Build files are formatted without being compiled.
+    if let Some(Tool::Format) = &arguments.tool {
+        let paths = if arguments.outputs.is_empty() {
+            let mut paths = parse_modules(context, &root_module_path)
+                .await?
+                .0
+                .into_keys()
+                .collect::<Vec<_>>();
+            paths.sort();
+            paths
+        } else {
+            arguments.outputs.iter().map(PathBuf::from).collect()
+        };
+
+        return tool::format(context, &paths, arguments.check).await;
+    }
+
     let (modules, dependencies) = parse_modules(context, &root_module_path).await?;
 
     module_dependency::validate(&dependencies)?;
 
-    let configuration = Arc::new(compile(&modules, &dependencies, &root_module_path)?);
+    let configuration = {
+        let _slice = context.trace().start("compile", "compile");
+
+        Arc::new(compile(&modules, &dependencies, &root_module_path)?)
+    };
+
+    if matches!(arguments.tool, Some(Tool::Lint)) || arguments.lint && arguments.tool.is_none() {
+        let diagnostics = lint(&modules, &dependencies, &root_module_path)?;
+
+        if arguments.tool.is_some() {
+            return tool::lint(context, &diagnostics).await;
+        }
+
+        tool::write_lint_warnings(
+            context,
+            &diagnostics,
+            arguments.log_prefix.as_deref().unwrap_or_default(),
+        )
+        .await?;
+    }
 
-    context.database().initialize(
-        &configuration
-            .build_directory()
-            .map(|string| string.as_ref().as_ref())
-            .unwrap_or_else(|| root_module_path.parent().unwrap())
-            .join(DATABASE_DIRECTORY)
-            .join(env!("CARGO_PKG_VERSION").replace('.', "_")),
-    )?;
+    let database_directory = database_directory(
+        configuration.build_directory().map(AsRef::as_ref),
+        &root_module_path,
+    );
+
+    context
+        .database()
+        .initialize(&database_directory.join(env!("CARGO_PKG_VERSION").replace('.', "_")))?;
 
     if let Some(tool) = &arguments.tool {
         match tool {
+            Tool::Affected => {
+                tool::affected(
+                    context,
+                    &configuration,
+                    &arguments.outputs,
+                    arguments.default_only,
+                    arguments.rule.as_deref(),
+                    arguments.print0,
+                )
+                .await?
+            }
+            Tool::Browse => {
+                tool::browse(context, &configuration, &arguments.outputs, arguments.port).await?
+            }
             Tool::CleanDead => tool::clean_dead(context, &configuration).await?,
+            Tool::Commands => {
+                tool::commands(
+                    context,
+                    &configuration,
+                    &arguments.outputs,
+                    arguments.final_only,
+                )
+                .await?
+            }
+            Tool::Completions => unreachable!(),
+            Tool::CompilationDatabase => {
+                tool::compilation_database(context, &configuration, &arguments.outputs).await?
+            }
+            Tool::Format => unreachable!(),
+            Tool::Graph => {
+                tool::graph(
+                    context,
+                    &configuration,
+                    &arguments.outputs,
+                    arguments.dynamic_inputs,
+                )
+                .await?
+            }
+            Tool::Inputs => {
+                tool::inputs(
+                    context,
+                    &configuration,
+                    &arguments.outputs,
+                    arguments.print0,
+                )
+                .await?
+            }
+            Tool::LastFailures => tool::last_failures(context, &configuration).await?,
+            Tool::Lint => unreachable!(),
+            Tool::Log => tool::log(context, &configuration, &arguments.outputs).await?,
+            Tool::Lsp => unreachable!(),
+            Tool::MissingDependencies => {
+                tool::missing_dependencies(context, &configuration).await?
+            }
+            Tool::Outputs => {
+                tool::outputs(
+                    context,
+                    &configuration,
+                    &arguments.outputs,
+                    arguments.print0,
+                )
+                .await?
+            }
+            Tool::Query => tool::query(context, &configuration, &arguments.outputs).await?,
+            Tool::Rules => tool::rules(context, &configuration).await?,
+            Tool::Targets => tool::targets(context, &configuration, &arguments.outputs).await?,
         }
     } else {
         run::run(
             context,
             configuration.clone(),
             &arguments.outputs,
+            arguments.rule.as_deref(),
             run::Options {
+                verbose: arguments.verbose,
                 debug: arguments.debug,
                 profile: arguments.profile,
+                summary: arguments.summary,
+                map_sources: arguments.map_sources,
+                junit: arguments.junit.clone(),
+                log_directory: database_directory.join(LOG_DIRECTORY),
             },
         )
         .await?;
@@ -132,6 +304,119 @@ async fn execute(context: &Arc<Context>, arguments: &Arguments) -> Result<(), Ap
     Ok(())
 }
 
+async fn complete(
+    context: &Context,
+    arguments: &Arguments,
+    kind: CompletionKind,
+) -> Result<(), ApplicationError> {
+    let candidates = if let CompletionKind::Tools = kind {
+        Tool::value_variants()
+            .iter()
+            .filter_map(|tool| Some(tool.to_possible_value()?.get_name().to_owned()))
+            .collect()
+    } else {
+        let root_module_path = context
+            .file_system()
+            .canonicalize_path(
+                arguments
+                    .file
+                    .as_deref()
+                    .unwrap_or(DEFAULT_BUILD_FILE)
+                    .as_ref(),
+            )
+            .await?;
+        // Caches are looked up in a build directory in a root module without
+        // compiling all modules.
+        let mut source = String::new();
+        context
+            .file_system()
+            .read_file_to_string(&root_module_path, &mut source)
+            .await?;
+        let build_directory = parse(&source)?
+            .statements()
+            .iter()
+            .rev()
+            .find_map(|statement| match statement {
+                Statement::VariableDefinition(definition)
+                    if definition.name() == BUILD_DIRECTORY_VARIABLE =>
+                {
+                    Some(definition.value().to_owned())
+                }
+                _ => None,
+            });
+        let cached = if let Some(build_directory) = &build_directory
+            && build_directory.contains('$')
+        {
+            None
+        } else {
+            Cache::read(
+                context,
+                &database_directory(build_directory.as_deref(), &root_module_path)
+                    .join(COMPLETION_CACHE_FILE),
+            )
+            .await
+        };
+
+        let cache = if let Some(cache) = cached {
+            cache
+        } else {
+            let (modules, dependencies) = parse_modules(context, &root_module_path).await?;
+
+            module_dependency::validate(&dependencies)?;
+
+            let mut times = vec![];
+
+            for path in modules.keys() {
+                times.push((
+                    path.clone(),
+                    context.file_system().metadata(path).await?.modified_time(),
+                ));
+            }
+
+            let configuration = compile(&modules, &dependencies, &root_module_path)?;
+            let cache = Cache::new(&configuration, times);
+            cache
+                .write(
+                    &database_directory(
+                        configuration.build_directory().map(AsRef::as_ref),
+                        &root_module_path,
+                    )
+                    .join(COMPLETION_CACHE_FILE),
+                )
+                .await?;
+            cache
+        };
+
+        if let CompletionKind::Rules = kind {
+            cache.rules().to_vec()
+        } else {
+            cache.targets().to_vec()
+        }
+    };
+
+    context
+        .console()
+        .lock()
+        .await
+        .write_stdout(
+            candidates
+                .iter()
+                .map(|candidate| format!("{candidate}\n"))
+                .collect::<String>()
+                .as_bytes(),
+        )
+        .await?;
+
+    Ok(())
+}
+
+fn database_directory(build_directory: Option<&str>, root_module_path: &Path) -> PathBuf {
+    build_directory
+        .map(Path::new)
+        .unwrap_or_else(|| root_module_path.parent().unwrap())
+        .join(DATABASE_DIRECTORY)
+}
+
 async fn parse_modules(
     context: &Context,
     path: &Path,
@@ -148,7 +433,11 @@ async fn parse_modules(
             .read_file_to_string(&path, &mut source)
             .await?;
 
-        let module = parse(&source)?;
+        let module = {
+            let _slice = context.trace().start("parse", path.display());
+
+            parse(&source)?
+        };
 
         let submodule_paths = try_join_all(
             module
@@ -6,9 +6,23 @@ use self::parser::{dynamic_module, module};
 use crate::ast::{DynamicModule, Module};
 
 pub fn parse(source: &str) -> Result<Module, ParseError> {
-    Ok(module(source).map(|(_, module)| module)?)
+    module(source)
+        .map(|(_, module)| module)
+        .map_err(|error| ParseError::new(source, error))
 }
 
 pub fn parse_dynamic(source: &str) -> Result<DynamicModule, ParseError> {
-    Ok(dynamic_module(source).map(|(_, module)| module)?)
+    dynamic_module(source)
+        .map(|(_, module)| module)
+        .map_err(|error| ParseError::new(source, error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_error_line() {
+        assert_eq!(parse("x = 1\nfoo\n").unwrap_err().line(), Some(2));
+    }
 }
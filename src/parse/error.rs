@@ -6,19 +6,27 @@ use std::{
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ParseError {
     message: String,
+    line: Option<usize>,
 }
 
 impl ParseError {
-    pub fn new(message: impl Into<String>) -> Self {
+    pub fn new(source: &str, error: nom::Err<nom::error::Error<&str>>) -> Self {
         Self {
-            message: message.into(),
+            message: error.to_string(),
+            line: match &error {
+                nom::Err::Error(error) | nom::Err::Failure(error) => Some(
+                    source[..source.len() - error.input.len()]
+                        .matches('\n')
+                        .count()
+                        + 1,
+                ),
+                nom::Err::Incomplete(_) => None,
+            },
         }
     }
-}
 
-impl From<nom::Err<nom::error::Error<&str>>> for ParseError {
-    fn from(error: nom::Err<nom::error::Error<&str>>) -> Self {
-        Self::new(error.to_string())
+    pub fn line(&self) -> Option<usize> {
+        self.line
     }
 }
 
@@ -1,15 +1,15 @@
 use crate::ast::{
-    Build, DefaultOutput, DynamicBuild, DynamicModule, Include, Module, Rule, Statement, Submodule,
-    VariableDefinition,
+    Build, Comment, Comments, DefaultOutput, DynamicBuild, DynamicModule, Include, Module, Rule,
+    Statement, Submodule, VariableDefinition,
 };
 use nom::{
     IResult, Parser,
     branch::alt,
     bytes::complete::tag,
-    character::complete::{alpha1, alphanumeric1, line_ending, none_of, one_of, space1},
-    combinator::{all_consuming, into, map, not, opt, peek, recognize, value},
+    character::complete::{alpha1, alphanumeric1, line_ending, none_of, one_of, space0, space1},
+    combinator::{all_consuming, into, map, not, opt, peek, recognize, rest_len, value},
     multi::{many0, many0_count, many1, many1_count},
-    sequence::{delimited, preceded, terminated},
+    sequence::{preceded, terminated},
 };
 
 const OPERATOR_CHARACTERS: &str = "|:";
@@ -17,8 +17,19 @@ const DYNAMIC_MODULE_VERSION_VARIABLE: &str = "ninja_dyndep_version";
 
 pub fn module(input: &str) -> IResult<&str, Module> {
     map(
-        all_consuming((opt(line_break), many0(statement), opt(line_break))),
-        |(_, statements, _)| Module::new(statements),
+        all_consuming((
+            many0_count((space0, line_ending)),
+            many0((rest_len, statement)),
+            opt(line_break),
+        )),
+        |(_, statements, _)| {
+            let (offsets, statements) = statements
+                .into_iter()
+                .map(|(length, statement)| (input.len() - length, statement))
+                .unzip::<_, _, Vec<_>, _>();
+
+            Module::new(statements).with_lines(line_numbers(input, &offsets))
+        },
     )
     .parse(input)
 }
@@ -26,11 +37,12 @@ pub fn module(input: &str) -> IResult<&str, Module> {
 pub fn dynamic_module(input: &str) -> IResult<&str, DynamicModule> {
     map(
         all_consuming((
-            opt(line_break),
+            blank_lines,
             dynamic_module_version,
-            many0(dynamic_build),
+            many0(preceded(blank_lines, dynamic_build)),
+            blank_lines,
         )),
-        |(_, _, builds)| DynamicModule::new(builds),
+        |(_, _, builds, _)| DynamicModule::new(builds),
     )
     .parse(input)
 }
@@ -38,6 +50,7 @@ pub fn dynamic_module(input: &str) -> IResult<&str, DynamicModule> {
 fn statement(input: &str) -> IResult<&str, Statement> {
     alt((
         into(build),
+        into(comment_statement),
         into(default),
         into(include),
         into(rule),
@@ -55,7 +68,31 @@ fn variable_definition(input: &str) -> IResult<&str, VariableDefinition> {
     .parse(input)
 }
 
-fn dynamic_module_version(input: &str) -> IResult<&str, &str> {
+fn comment_statement(input: &str) -> IResult<&str, Comment> {
+    terminated(comment_text, line_break).parse(input)
+}
+
+fn comment_text(input: &str) -> IResult<&str, Comment> {
+    map(
+        (space0, tag("#"), recognize(many0_count(none_of("\n")))),
+        |(_, _, text): (_, _, &str)| Comment::new(text.trim_end()),
+    )
+    .parse(input)
+}
+
+// Comment lines and empty lines before a line in a statement body
+fn comment_lines(input: &str) -> IResult<&str, Vec<Comment>> {
+    map(
+        many0(alt((
+            map(comment_statement, Some),
+            value(None, (blank, line_ending)),
+        ))),
+        |comments| comments.into_iter().flatten().collect(),
+    )
+    .parse(input)
+}
+
+fn dynamic_module_version(input: &str) -> IResult<&str, String> {
     map(
         (
             keyword(DYNAMIC_MODULE_VERSION_VARIABLE),
@@ -73,23 +110,49 @@ fn rule(input: &str) -> IResult<&str, Rule> {
         (
             keyword("rule"),
             identifier,
+            opt(comment_text),
             line_break,
-            delimited(
-                (indent, keyword("command"), sign("=")),
-                string_line,
-                line_break,
-            ),
-            opt(delimited(
-                (indent, keyword("description"), sign("=")),
-                string_line,
-                line_break,
-            )),
+            rule_binding("command"),
+            opt(rule_binding("description")),
+            opt(rule_binding("replay")),
         ),
-        |(_, name, _, command, description)| Rule::new(name, command, description.map(From::from)),
+        |(_, name, trailing_comment, _, command, description, replay)| {
+            let bindings = [Some(command), description, replay];
+            let body = bindings
+                .iter()
+                .flatten()
+                .enumerate()
+                .flat_map(|(index, (comments, _))| {
+                    comments.iter().map(move |comment| (index, comment.clone()))
+                })
+                .collect();
+            let [command, description, replay] =
+                bindings.map(|binding| binding.map(|(_, value)| value));
+
+            Rule::new(name, command.unwrap_or_default(), description, replay)
+                .with_comments(Comments::new(trailing_comment, body))
+        },
     )
     .parse(input)
 }
 
+fn rule_binding(name: &'static str) -> impl Fn(&str) -> IResult<&str, (Vec<Comment>, String)> {
+    move |input| {
+        map(
+            (
+                comment_lines,
+                indent,
+                keyword(name),
+                sign("="),
+                string_line,
+                line_break,
+            ),
+            |(comments, _, _, _, value, _)| (comments, value),
+        )
+        .parse(input)
+    }
+}
+
 fn build(input: &str) -> IResult<&str, Build> {
     map(
         (
@@ -101,8 +164,9 @@ fn build(input: &str) -> IResult<&str, Build> {
             many0(string_literal),
             opt(preceded(sign("|"), many1(string_literal))),
             opt(preceded(sign("||"), many1(string_literal))),
+            opt(comment_text),
             line_break,
-            many0(preceded(indent, variable_definition)),
+            many0((comment_lines, preceded(indent, variable_definition))),
         ),
         |(
             _,
@@ -113,9 +177,18 @@ fn build(input: &str) -> IResult<&str, Build> {
             inputs,
             implicit_inputs,
             order_only_inputs,
+            trailing_comment,
             _,
             variable_definitions,
         )| {
+            let body = variable_definitions
+                .iter()
+                .enumerate()
+                .flat_map(|(index, (comments, _))| {
+                    comments.iter().map(move |comment| (index, comment.clone()))
+                })
+                .collect();
+
             Build::new(
                 outputs,
                 implicit_outputs.unwrap_or_default(),
@@ -123,8 +196,12 @@ fn build(input: &str) -> IResult<&str, Build> {
                 inputs,
                 implicit_inputs.unwrap_or_default(),
                 order_only_inputs.unwrap_or_default(),
-                variable_definitions,
+                variable_definitions
+                    .into_iter()
+                    .map(|(_, definition)| definition)
+                    .collect(),
             )
+            .with_comments(Comments::new(trailing_comment, body))
         },
     )
     .parse(input)
@@ -149,8 +226,16 @@ pub fn dynamic_build(input: &str) -> IResult<&str, DynamicBuild> {
 
 fn default(input: &str) -> IResult<&str, DefaultOutput> {
     map(
-        (keyword("default"), many1(string_literal), line_break),
-        |(_, outputs, _)| DefaultOutput::new(outputs.into_iter().collect()),
+        (
+            keyword("default"),
+            many1(string_literal),
+            opt(comment_text),
+            line_break,
+        ),
+        |(_, outputs, trailing_comment, _)| {
+            DefaultOutput::new(outputs.into_iter().collect())
+                .with_comments(Comments::new(trailing_comment, vec![]))
+        },
     )
     .parse(input)
 }
@@ -171,9 +256,9 @@ fn submodule(input: &str) -> IResult<&str, Submodule> {
     .parse(input)
 }
 
-fn string_line(input: &str) -> IResult<&str, &str> {
+fn string_line(input: &str) -> IResult<&str, String> {
     map(recognize(many1_count(none_of("\n"))), |string: &str| {
-        string.trim()
+        string.trim().into()
     })
     .parse(input)
 }
@@ -235,8 +320,29 @@ fn comment(input: &str) -> IResult<&str, ()> {
     value((), (tag("#"), many0_count(none_of("\n")))).parse(input)
 }
 
+// Empty lines are skipped but comment lines are not because they are
+// statements.
 fn line_break(input: &str) -> IResult<&str, ()> {
-    value((), many1_count((blank, line_ending))).parse(input)
+    value((), (blank, line_ending, many0_count((space0, line_ending)))).parse(input)
+}
+
+// Offsets need to be sorted.
+fn line_numbers(input: &str, offsets: &[usize]) -> Vec<usize> {
+    let mut line = 1;
+    let mut last_offset = 0;
+
+    offsets
+        .iter()
+        .map(|&offset| {
+            line += input[last_offset..offset].matches('\n').count();
+            last_offset = offset;
+            line
+        })
+        .collect()
+}
+
+fn blank_lines(input: &str) -> IResult<&str, ()> {
+    value((), many0_count((blank, line_ending))).parse(input)
 }
 
 #[cfg(test)]
@@ -263,7 +369,17 @@ mod tests {
     #[test]
     fn parse_module() {
         assert_eq!(module("").unwrap().1, Module::new(vec![]));
-        assert_eq!(module("#foo\n").unwrap().1, Module::new(vec![]));
+        assert_eq!(
+            module("#foo\n").unwrap().1,
+            Module::new(vec![Comment::new("foo").into()])
+        );
+        assert_eq!(
+            module("\n# foo\n\nx = 42\n").unwrap().1,
+            Module::new(vec![
+                Comment::new(" foo").into(),
+                VariableDefinition::new("x", "42").into()
+            ])
+        );
         assert_eq!(
             module("x = 42\n").unwrap().1,
             Module::new(vec![VariableDefinition::new("x", "42").into()])
@@ -277,15 +393,15 @@ mod tests {
         );
         assert_eq!(
             module("rule foo\n command = bar\n").unwrap().1,
-            Module::new(vec![Rule::new("foo", "bar", None).into()])
+            Module::new(vec![Rule::new("foo", "bar", None, None).into()])
         );
         assert_eq!(
             module("rule foo\n command = bar\nrule baz\n command = blah\n")
                 .unwrap()
                 .1,
             Module::new(vec![
-                Rule::new("foo", "bar", None).into(),
-                Rule::new("baz", "blah", None).into(),
+                Rule::new("foo", "bar", None, None).into(),
+                Rule::new("baz", "blah", None, None).into(),
             ],)
         );
         assert_eq!(
@@ -294,6 +410,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_module_lines() {
+        let module = module("\nx = 1\n\nrule foo\n  command = bar\n# baz\nbuild foo: bar\n")
+            .unwrap()
+            .1;
+
+        assert_eq!(
+            (0..5).map(|index| module.line(index)).collect::<Vec<_>>(),
+            [Some(2), Some(4), Some(6), Some(7), None]
+        );
+    }
+
     #[test]
     fn parse_dynamic_module() {
         assert_eq!(
@@ -351,13 +479,65 @@ mod tests {
     fn parse_rule() {
         assert_eq!(
             rule("rule foo\n command = bar\n").unwrap().1,
-            Rule::new("foo", "bar", None)
+            Rule::new("foo", "bar", None, None)
         );
         assert_eq!(
             rule("rule foo\n command = bar\n description = baz\n")
                 .unwrap()
                 .1,
-            Rule::new("foo", "bar", Some("baz".into()))
+            Rule::new("foo", "bar", Some("baz".into()), None)
+        );
+        assert_eq!(
+            rule("rule foo\n command = bar\n description = baz\n replay = 1\n")
+                .unwrap()
+                .1,
+            Rule::new("foo", "bar", Some("baz".into()), Some("1".into()))
+        );
+        assert_eq!(
+            rule("rule foo\n command = bar\n replay = 1\n").unwrap().1,
+            Rule::new("foo", "bar", None, Some("1".into()))
+        );
+    }
+
+    #[test]
+    fn parse_rule_with_comments() {
+        assert_eq!(
+            rule("rule foo # a\n  # b\n command = bar\n\n  # c\n description = baz\n")
+                .unwrap()
+                .1,
+            Rule::new("foo", "bar", Some("baz".into()), None).with_comments(Comments::new(
+                Some(Comment::new(" a")),
+                vec![(0, Comment::new(" b")), (1, Comment::new(" c"))]
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_build_with_comments() {
+        assert_eq!(
+            build("build foo: bar # a\n  # b\n  x = 1\n  # c\n").unwrap(),
+            (
+                "  # c\n",
+                explicit_build(
+                    vec!["foo".into()],
+                    "bar",
+                    vec![],
+                    vec![VariableDefinition::new("x", "1")]
+                )
+                .with_comments(Comments::new(
+                    Some(Comment::new(" a")),
+                    vec![(0, Comment::new(" b"))]
+                ))
+            )
+        );
+    }
+
+    #[test]
+    fn parse_default_with_comment() {
+        assert_eq!(
+            default("default foo # bar\n").unwrap().1,
+            DefaultOutput::new(vec!["foo".into()])
+                .with_comments(Comments::new(Some(Comment::new(" bar")), vec![]))
         );
     }
 
@@ -531,6 +711,17 @@ mod tests {
         assert_eq!(string_line("foo bar").unwrap().1, "foo bar");
     }
 
+    #[test]
+    fn parse_comment_statement() {
+        assert_eq!(comment_statement("#\n").unwrap().1, Comment::new(""));
+        assert_eq!(comment_statement("#foo\n").unwrap().1, Comment::new("foo"));
+        assert_eq!(
+            comment_statement("# foo \n").unwrap().1,
+            Comment::new(" foo")
+        );
+        assert!(comment_statement("foo\n").is_err());
+    }
+
     #[test]
     fn parse_string_literal() {
         assert!(string_literal("").is_err());
@@ -575,5 +766,6 @@ mod tests {
         assert!(all_consuming(line_break).parse("  \n").is_ok());
         assert!(all_consuming(line_break).parse("\n\n").is_ok());
         assert!(all_consuming(line_break).parse("\n ").is_err());
+        assert!(all_consuming(line_break).parse("\n#foo\n").is_err());
     }
 }
@@ -0,0 +1,405 @@
+use crate::ast::{Build, Comment, Comments, Module, Rule, Statement, VariableDefinition};
+
+const INDENT: &str = "  ";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Group {
+    Build,
+    Default,
+    Include,
+    Standalone,
+    VariableDefinition,
+}
+
+pub fn print(module: &Module) -> String {
+    let mut string = String::new();
+    let mut last_group = None;
+
+    for (index, statement) in module.statements().iter().enumerate() {
+        // Comments belong to the next non-comment statement.
+        let group = module.statements()[index..]
+            .iter()
+            .find(|statement| !matches!(statement, Statement::Comment(_)))
+            .map(statement_group)
+            .unwrap_or(Group::Standalone);
+
+        // Comment blocks after statements start sections.
+        if let Some(last_group) = last_group
+            && (last_group != group
+                || group == Group::Standalone
+                || matches!(statement, Statement::Comment(_)))
+        {
+            string.push('\n');
+        }
+
+        if let Statement::Comment(comment) = statement {
+            print_comment(&mut string, "", comment);
+            last_group = None;
+            continue;
+        }
+
+        print_statement(&mut string, statement);
+        last_group = Some(group);
+    }
+
+    string
+}
+
+fn statement_group(statement: &Statement) -> Group {
+    match statement {
+        Statement::Build(build) if build.variable_definitions().is_empty() => Group::Build,
+        Statement::Default(_) => Group::Default,
+        Statement::Include(_) | Statement::Submodule(_) => Group::Include,
+        Statement::VariableDefinition(_) => Group::VariableDefinition,
+        Statement::Build(_) | Statement::Comment(_) | Statement::Rule(_) => Group::Standalone,
+    }
+}
+
+fn print_statement(string: &mut String, statement: &Statement) {
+    match statement {
+        Statement::Build(build) => print_build(string, build),
+        Statement::Comment(_) => {}
+        Statement::Default(default) => {
+            print_line(
+                string,
+                "",
+                ["default"]
+                    .into_iter()
+                    .chain(default.outputs().iter().map(String::as_str)),
+            );
+            print_trailing_comment(string, default.comments());
+        }
+        Statement::Include(include) => print_line(string, "", ["include", include.path()]),
+        Statement::Rule(rule) => print_rule(string, rule),
+        Statement::Submodule(submodule) => print_line(string, "", ["subninja", submodule.path()]),
+        Statement::VariableDefinition(definition) => {
+            print_variable_definition(string, "", definition)
+        }
+    }
+}
+
+fn print_rule(string: &mut String, rule: &Rule) {
+    print_line(string, "", ["rule", rule.name()]);
+    print_trailing_comment(string, rule.comments());
+
+    for (index, definition) in [
+        Some(VariableDefinition::new("command", rule.command())),
+        rule.description()
+            .map(|description| VariableDefinition::new("description", description)),
+        rule.replay()
+            .map(|replay| VariableDefinition::new("replay", replay)),
+    ]
+    .into_iter()
+    .flatten()
+    .enumerate()
+    {
+        print_body_comments(string, rule.comments(), index);
+        print_variable_definition(string, INDENT, &definition);
+    }
+}
+
+fn print_build(string: &mut String, build: &Build) {
+    let mut words = vec!["build".to_owned()];
+
+    words.extend(build.outputs().iter().cloned());
+
+    if !build.implicit_outputs().is_empty() {
+        words.push("|".into());
+        words.extend(build.implicit_outputs().iter().cloned());
+    }
+
+    if let Some(word) = words.last_mut() {
+        word.push(':');
+    }
+
+    words.push(build.rule().into());
+    words.extend(build.inputs().iter().cloned());
+
+    for (operator, inputs) in [
+        ("|", build.implicit_inputs()),
+        ("||", build.order_only_inputs()),
+    ] {
+        if !inputs.is_empty() {
+            words.push(operator.into());
+            words.extend(inputs.iter().cloned());
+        }
+    }
+
+    print_line(string, "", words.iter().map(String::as_str));
+    print_trailing_comment(string, build.comments());
+
+    for (index, definition) in build.variable_definitions().iter().enumerate() {
+        print_body_comments(string, build.comments(), index);
+        print_variable_definition(string, INDENT, definition);
+    }
+}
+
+fn print_comment(string: &mut String, indent: &str, comment: &Comment) {
+    string.push_str(indent);
+    string.push('#');
+    string.push_str(comment.text());
+    string.push('\n');
+}
+
+// Trailing comments are appended to last lines of statements.
+fn print_trailing_comment(string: &mut String, comments: &Comments) {
+    if let Some(comment) = comments.trailing() {
+        string.pop();
+        string.push(' ');
+        print_comment(string, "", comment);
+    }
+}
+
+fn print_body_comments(string: &mut String, comments: &Comments, index: usize) {
+    for (_, comment) in comments.body().iter().filter(|(line, _)| *line == index) {
+        print_comment(string, INDENT, comment);
+    }
+}
+
+fn print_variable_definition(string: &mut String, indent: &str, definition: &VariableDefinition) {
+    let head = format!("{} =", definition.name());
+
+    print_line(
+        string,
+        indent,
+        [head.as_str(), definition.value()]
+            .into_iter()
+            .filter(|word| !word.is_empty()),
+    );
+}
+
+fn print_line<'a>(string: &mut String, indent: &str, words: impl IntoIterator<Item = &'a str>) {
+    string.push_str(indent);
+
+    for (index, word) in words.into_iter().enumerate() {
+        if index > 0 {
+            string.push(' ');
+        }
+
+        string.push_str(word);
+    }
+
+    string.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ast::{Comment, DefaultOutput, Include, Submodule},
+        parse::parse,
+    };
+    use pretty_assertions::assert_eq;
+    use proptest::{collection::vec, option, prelude::*};
+
+    const KEYWORDS: &[&str] = &["build", "default", "include", "rule", "subninja"];
+
+    fn identifier() -> impl Strategy<Value = String> {
+        "[a-z_][a-z0-9_]{0,8}".prop_filter("keyword", |name| {
+            !KEYWORDS.iter().any(|keyword| name.starts_with(keyword))
+        })
+    }
+
+    fn path() -> impl Strategy<Value = String> {
+        "[a-z0-9_./-]{1,16}"
+    }
+
+    fn value() -> impl Strategy<Value = String> {
+        vec("([a-z0-9_./=-]|\\$\\$|\\$\\{[a-z]{1,4}\\}){1,12}", 1..12)
+            .prop_map(|words| words.join(" "))
+    }
+
+    fn comment() -> impl Strategy<Value = Comment> {
+        "[ -~]{0,40}".prop_map(|text| Comment::new(text.trim_end()))
+    }
+
+    // Comments of a statement with a number of body lines
+    fn comments(lines: usize) -> impl Strategy<Value = Comments> {
+        (
+            option::of(comment()),
+            vec((0..lines.max(1), comment()), 0..lines.min(3) + 1),
+        )
+            .prop_map(|(trailing, mut body)| {
+                body.sort_by_key(|(index, _)| *index);
+                Comments::new(trailing, body)
+            })
+    }
+
+    fn variable_definition() -> impl Strategy<Value = VariableDefinition> {
+        (identifier(), option::of(value()))
+            .prop_map(|(name, value)| VariableDefinition::new(name, value.unwrap_or_default()))
+    }
+
+    fn statement() -> impl Strategy<Value = Statement> {
+        prop_oneof![
+            (
+                vec(path(), 1..4),
+                vec(path(), 0..3),
+                identifier(),
+                vec(path(), 0..8),
+                vec(path(), 0..3),
+                vec(path(), 0..3),
+                vec(variable_definition(), 0..3),
+            )
+                .prop_map(
+                    |(
+                        outputs,
+                        implicit_outputs,
+                        rule,
+                        inputs,
+                        implicit_inputs,
+                        order_only_inputs,
+                        variable_definitions,
+                    )| {
+                        Build::new(
+                            outputs,
+                            implicit_outputs,
+                            rule,
+                            inputs,
+                            implicit_inputs,
+                            order_only_inputs,
+                            variable_definitions,
+                        )
+                    }
+                )
+                .prop_flat_map(|build| {
+                    let lines = build.variable_definitions().len();
+
+                    (Just(build), comments(lines))
+                })
+                .prop_map(|(build, comments)| build.with_comments(comments).into()),
+            comment().prop_map(Statement::from),
+            (vec(path(), 1..8), comments(0)).prop_map(|(outputs, comments)| {
+                DefaultOutput::new(outputs).with_comments(comments).into()
+            }),
+            path().prop_map(|path| Include::new(path).into()),
+            (
+                identifier(),
+                value(),
+                option::of(value()),
+                option::of(value())
+            )
+                .prop_flat_map(|(name, command, description, replay)| {
+                    let lines = 1 + description.is_some() as usize + replay.is_some() as usize;
+
+                    (
+                        Just(Rule::new(name, command, description, replay)),
+                        comments(lines),
+                    )
+                })
+                .prop_map(|(rule, comments)| rule.with_comments(comments).into()),
+            path().prop_map(|path| Submodule::new(path).into()),
+            variable_definition().prop_map(Statement::from),
+        ]
+    }
+
+    #[test]
+    fn print_empty_module() {
+        assert_eq!(print(&Module::new(vec![])), "");
+    }
+
+    #[test]
+    fn print_module() {
+        assert_eq!(
+            print(&Module::new(vec![
+                Comment::new(" foo").into(),
+                VariableDefinition::new("x", "1").into(),
+                VariableDefinition::new("y", "").into(),
+                Rule::new("cc", "cc -o $out $in", Some("cc $out".into()), None).into(),
+                Build::new(
+                    vec!["foo.o".into()],
+                    vec![],
+                    "cc",
+                    vec!["foo.c".into()],
+                    vec![],
+                    vec![],
+                    vec![]
+                )
+                .into(),
+                Build::new(
+                    vec!["bar.o".into()],
+                    vec![],
+                    "cc",
+                    vec!["bar.c".into()],
+                    vec!["bar.h".into()],
+                    vec!["baz".into()],
+                    vec![]
+                )
+                .into(),
+                DefaultOutput::new(vec!["foo.o".into()]).into(),
+            ])),
+            "# foo\nx = 1\ny =\n\nrule cc\n  command = cc -o $out $in\n  description = cc $out\n\n\
+             build foo.o: cc foo.c\nbuild bar.o: cc bar.c | bar.h || baz\n\ndefault foo.o\n"
+        );
+    }
+
+    #[test]
+    fn print_comments() {
+        assert_eq!(
+            print(&Module::new(vec![
+                Rule::new("cc", "cc", Some("cc".into()), None)
+                    .with_comments(Comments::new(
+                        Some(Comment::new(" foo")),
+                        vec![(0, Comment::new(" bar")), (1, Comment::new(" baz"))]
+                    ))
+                    .into(),
+                Build::new(
+                    vec!["foo.o".into()],
+                    vec![],
+                    "cc",
+                    vec![],
+                    vec![],
+                    vec![],
+                    vec![VariableDefinition::new("x", "1")]
+                )
+                .with_comments(Comments::new(
+                    Some(Comment::new(" qux")),
+                    vec![(0, Comment::new(" quux"))]
+                ))
+                .into(),
+            ])),
+            "rule cc # foo\n  # bar\n  command = cc\n  # baz\n  description = cc\n\n\
+             build foo.o: cc # qux\n  # quux\n  x = 1\n"
+        );
+    }
+
+    #[test]
+    fn print_comment_sections() {
+        let source = "# foo\nx = 1\n\n# bar\ny = 2\n";
+
+        assert_eq!(print(&parse(source).unwrap()), source);
+    }
+
+    #[test]
+    fn print_long_line() {
+        assert_eq!(
+            print(&Module::new(vec![
+                VariableDefinition::new("x", ["foo"; 20].join(" ")).into()
+            ])),
+            format!("x = {}\n", ["foo"; 20].join(" "))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn print_and_parse(statements in vec(statement(), 0..16)) {
+            let module = Module::new(statements);
+
+            prop_assert_eq!(parse(&print(&module)).unwrap(), module);
+        }
+
+        #[test]
+        fn separate_comment_blocks(statements in vec(statement(), 0..16)) {
+            let string = print(&Module::new(statements));
+            let lines = string.lines().collect::<Vec<_>>();
+
+            for lines in lines.windows(2) {
+                prop_assert!(
+                    !lines[1].starts_with('#') || lines[0].is_empty() || lines[0].starts_with('#'),
+                    "{:?}",
+                    lines
+                );
+            }
+        }
+    }
+}
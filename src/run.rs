@@ -1,9 +1,14 @@
 mod context;
+mod dirty;
 mod hash;
+mod junit;
 mod log;
 mod options;
+mod source_map;
+mod summary;
+mod target;
 
-use self::context::Context as RunContext;
+use self::{context::Context as RunContext, target::resolve_targets};
 use crate::{
     build_graph::{BuildGraph, BuildGraphError},
     compile::compile_dynamic,
@@ -11,26 +16,37 @@ use crate::{
     debug,
     error::ApplicationError,
     hash_type::HashType,
+    infrastructure::Job,
     ir::{Build, Configuration, Rule},
+    log,
     parse::parse_dynamic,
     profile,
+    trace::Slice,
 };
 use async_recursion::async_recursion;
 use futures::future::{FutureExt, Shared, try_join_all};
 use itertools::Itertools;
 pub use options::Options;
-use std::{future::Future, path::Path, pin::Pin, sync::Arc};
-use tokio::{spawn, time::Instant, try_join};
+use std::{future::Future, path::Path, pin::Pin, process::Output, sync::Arc, time::Duration};
+use tokio::{
+    fs::write,
+    spawn,
+    time::{Instant, interval},
+};
 
 type RawBuildFuture = Pin<Box<dyn Future<Output = Result<(), ApplicationError>> + Send>>;
 type BuildFuture = Shared<RawBuildFuture>;
 
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
 pub async fn run(
     context: &Arc<Context>,
     configuration: Arc<Configuration>,
     outputs: &[String],
+    rule: Option<&str>,
     options: Options,
 ) -> Result<(), ApplicationError> {
+    let start_time = Instant::now();
     let graph = BuildGraph::new(configuration.outputs());
     let context = Arc::new(RunContext::new(
         context.clone(),
@@ -39,37 +55,58 @@ pub async fn run(
         options,
     ));
 
-    context
-        .build_graph()
-        .lock()
-        .await
-        .validate()
-        .map_err(|error| map_build_graph_error(&context, &error))?;
+    {
+        let _slice = context.application().trace().start("validate", "validate");
 
-    if outputs.is_empty() {
-        for output in context.configuration().default_outputs() {
-            trigger_build(
-                context.clone(),
+        context
+            .build_graph()
+            .lock()
+            .await
+            .validate()
+            .map_err(|error| map_build_graph_error(&context, &error))?;
+    }
+
+    let builds = if outputs.is_empty() && rule.is_none() {
+        context
+            .configuration()
+            .default_outputs()
+            .iter()
+            .map(|output| {
                 context
                     .configuration()
                     .outputs()
                     .get(output.as_ref())
-                    .ok_or_else(|| ApplicationError::DefaultOutputNotFound(output.clone()))?,
-            )
-            .await?;
-        }
+                    .ok_or_else(|| ApplicationError::DefaultOutputNotFound(output.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?
     } else {
-        for output in outputs {
-            trigger_build(
-                context.clone(),
-                context
-                    .configuration()
-                    .outputs()
-                    .get(output.as_str())
-                    .ok_or_else(|| ApplicationError::OutputNotFound(output.clone()))?,
-            )
-            .await?;
-        }
+        resolve_targets(context.configuration(), outputs, rule)?
+    };
+
+    context
+        .application()
+        .file_system()
+        .create_directory(&context.options().log_directory)
+        .await?;
+    context.application().database().clear_failures()?;
+
+    // Estimate builds to run accurately only if they are shown.
+    let jobs = if context.application().console().lock().await.needs_total() {
+        dirty::find_dirty_builds(&context, &builds).await?
+    } else {
+        dirty::find_reachable_jobs(&context, &builds).await?
+    };
+
+    context
+        .application()
+        .console()
+        .lock()
+        .await
+        .start_jobs(&jobs)
+        .await?;
+
+    for build in builds {
+        trigger_build(context.clone(), build).await?;
     }
 
     // Do not inline this to avoid borrowing a lock of builds.
@@ -79,8 +116,58 @@ pub async fn run(
         .map(|r#ref| r#ref.value().clone())
         .collect::<Vec<_>>();
 
+    // Redraw a status area periodically to update elapsed times.
+    let redraw = spawn({
+        let context = context.clone();
+
+        async move {
+            let mut interval = interval(REDRAW_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                if context
+                    .application()
+                    .console()
+                    .lock()
+                    .await
+                    .redraw()
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        }
+    });
+
     let result = try_join_all(futures).await;
 
+    redraw.abort();
+
+    {
+        let mut console = context.application().console().lock().await;
+
+        console.finish_jobs().await?;
+
+        if context.options().summary {
+            let summary = context
+                .summary()
+                .lock()
+                .unwrap()
+                .format(Instant::now() - start_time);
+
+            log!(console, "{}", summary);
+        }
+    }
+
+    // Reports are written even on build failures.
+    if let Some(path) = &context.options().junit {
+        let xml = context.junit_report().lock().unwrap().to_xml();
+
+        write(path, xml).await?;
+    }
+
     context.application().database().flush().await?;
 
     result.map(|_| ())
@@ -107,7 +194,12 @@ async fn spawn_build(context: Arc<RunContext>, build: Arc<Build>) -> Result<(),
     spawn(async move {
         let mut futures = vec![];
 
-        for input in build.inputs().iter().chain(build.order_only_inputs()) {
+        for input in build
+            .inputs()
+            .iter()
+            .chain(build.implicit_inputs())
+            .chain(build.order_only_inputs())
+        {
             futures.push(build_input(context.clone(), input).await?);
         }
 
@@ -115,6 +207,10 @@ async fn spawn_build(context: Arc<RunContext>, build: Arc<Build>) -> Result<(),
 
         // TODO Consider caching dynamic modules.
         let dynamic_configuration = if let Some(dynamic_module) = build.dynamic_module() {
+            let _slice = context
+                .application()
+                .trace()
+                .start("dyndep", dynamic_module);
             let mut source = String::new();
             context
                 .application()
@@ -146,6 +242,16 @@ async fn spawn_build(context: Arc<RunContext>, build: Arc<Build>) -> Result<(),
             &[]
         };
 
+        if dynamic_configuration.is_some() {
+            context.application().database().set_dynamic_inputs(
+                build.id(),
+                &dynamic_inputs
+                    .iter()
+                    .map(|input| input.as_ref())
+                    .collect::<Vec<_>>(),
+            )?;
+        }
+
         let mut futures = vec![];
 
         for input in dynamic_inputs {
@@ -154,29 +260,23 @@ async fn spawn_build(context: Arc<RunContext>, build: Arc<Build>) -> Result<(),
 
         try_join_all(futures).await?;
 
-        let outputs_exist = try_join_all(
-            build
-                .outputs()
-                .iter()
-                .chain(build.implicit_outputs())
-                .map(|path| check_file_existence(&context, path)),
-        )
-        .await
-        .is_ok();
-        let (file_inputs, phony_inputs) = build
-            .inputs()
-            .iter()
-            .chain(dynamic_inputs)
-            .map(|string| string.as_ref())
-            .partition::<Vec<_>, _>(|&input| {
-                if let Some(build) = context.configuration().outputs().get(input) {
-                    build.rule().is_some()
-                } else {
-                    true
-                }
-            });
-        let timestamp_hash =
-            hash::calculate_timestamp_hash(&context, &build, &file_inputs, &phony_inputs).await?;
+        let outputs_exist = check_outputs(&context, &build).await;
+        let (file_inputs, phony_inputs) =
+            partition_inputs(&context, &build, dynamic_inputs.iter().map(AsRef::as_ref));
+        let estimated_hash = context
+            .timestamp_hashes()
+            .remove(&build.id())
+            .map(|(_, hash)| hash);
+        // Dynamic inputs can be different from ones in estimation.
+        let timestamp_hash = if let Some(hash) =
+            estimated_hash.filter(|_| dynamic_configuration.is_none())
+        {
+            hash
+        } else {
+            let _slice = start_build_slice(&context, "timestamp hash", &build);
+
+            hash::calculate_timestamp_hash(&context, &build, &file_inputs, &phony_inputs).await?
+        };
 
         if outputs_exist
             && Some(timestamp_hash)
@@ -185,11 +285,14 @@ async fn spawn_build(context: Arc<RunContext>, build: Arc<Build>) -> Result<(),
                     .database()
                     .get_hash(HashType::Timestamp, build.id())?
         {
-            return Ok(());
+            return skip_build(&context, &build, HashType::Timestamp).await;
         }
 
-        let content_hash =
-            hash::calculate_content_hash(&context, &build, &file_inputs, &phony_inputs).await?;
+        let content_hash = {
+            let _slice = start_build_slice(&context, "content hash", &build);
+
+            hash::calculate_content_hash(&context, &build, &file_inputs, &phony_inputs).await?
+        };
 
         if outputs_exist
             && Some(content_hash)
@@ -198,8 +301,13 @@ async fn spawn_build(context: Arc<RunContext>, build: Arc<Build>) -> Result<(),
                     .database()
                     .get_hash(HashType::Content, build.id())?
         {
-            return Ok(());
+            return skip_build(&context, &build, HashType::Content).await;
         } else if let Some(rule) = build.rule() {
+            // Builds estimated clean are dirty actually, so are their dependents.
+            if estimated_hash.is_some() {
+                context.timestamp_hashes().clear();
+            }
+
             try_join_all(
                 build
                     .outputs()
@@ -209,7 +317,7 @@ async fn spawn_build(context: Arc<RunContext>, build: Arc<Build>) -> Result<(),
             )
             .await?;
 
-            run_rule(&context, rule).await?;
+            run_rule(&context, &build, rule).await?;
 
             for output in build.outputs() {
                 context.application().database().set_output(output)?;
@@ -238,6 +346,86 @@ async fn spawn_build(context: Arc<RunContext>, build: Arc<Build>) -> Result<(),
     .await?
 }
 
+fn job_name(build: &Build) -> &str {
+    build
+        .rule()
+        .and_then(Rule::description)
+        .unwrap_or(&build.outputs()[0])
+}
+
+fn job<'a>(context: &'a RunContext, build: &'a Build) -> Job<'a> {
+    Job::new(
+        build.id(),
+        build.outputs(),
+        build
+            .outputs()
+            .iter()
+            .find_map(|output| context.configuration().source_map().get(output))
+            .map(AsRef::as_ref),
+        build.rule().and_then(Rule::description),
+        build
+            .rule()
+            .filter(|_| context.options().verbose)
+            .map(Rule::command),
+    )
+}
+
+// Starts a trace slice labelled by a description or output of a build.
+fn start_build_slice<'a>(
+    context: &'a RunContext,
+    category: &'static str,
+    build: &Build,
+) -> Option<Slice<'a>> {
+    Some(
+        context
+            .application()
+            .trace()
+            .start(category, job_name(build))?
+            .with_argument(
+                "outputs",
+                build
+                    .outputs()
+                    .iter()
+                    .map(|output| output.to_string())
+                    .collect::<Vec<_>>(),
+            ),
+    )
+}
+
+async fn skip_build(
+    context: &RunContext,
+    build: &Build,
+    r#type: HashType,
+) -> Result<(), ApplicationError> {
+    if let Some(rule) = build.rule() {
+        context.summary().lock().unwrap().skip_job(r#type);
+
+        if context.options().junit.is_some() {
+            context
+                .junit_report()
+                .lock()
+                .unwrap()
+                .skip_job(job_name(build), rule.name());
+        }
+
+        let job = job(context, build);
+        let output = if rule.replay() {
+            read_log(context, build).await?
+        } else {
+            None
+        };
+        let mut console = context.application().console().lock().await;
+
+        console.skip_job(&job).await?;
+
+        if let Some((stdout, stderr)) = output {
+            console.replay_job(&job, &stdout, &stderr).await?;
+        }
+    }
+
+    Ok(())
+}
+
 async fn build_input(
     context: Arc<RunContext>,
     input: &str,
@@ -256,6 +444,39 @@ async fn build_input(
     )
 }
 
+async fn check_outputs(context: &RunContext, build: &Build) -> bool {
+    try_join_all(
+        build
+            .outputs()
+            .iter()
+            .chain(build.implicit_outputs())
+            .map(|path| check_file_existence(context, path)),
+    )
+    .await
+    .is_ok()
+}
+
+// Partitions inputs into files and outputs of phony builds.
+fn partition_inputs<'a>(
+    context: &RunContext,
+    build: &'a Build,
+    dynamic_inputs: impl IntoIterator<Item = &'a str>,
+) -> (Vec<&'a str>, Vec<&'a str>) {
+    build
+        .inputs()
+        .iter()
+        .chain(build.implicit_inputs())
+        .map(AsRef::as_ref)
+        .chain(dynamic_inputs)
+        .partition(|&input| {
+            if let Some(build) = context.configuration().outputs().get(input) {
+                build.rule().is_some()
+            } else {
+                true
+            }
+        })
+}
+
 async fn check_file_existence(context: &RunContext, path: &str) -> Result<(), ApplicationError> {
     if context
         .application()
@@ -291,36 +512,64 @@ async fn prepare_directory(
     Ok(())
 }
 
-async fn run_rule(context: &RunContext, rule: &Rule) -> Result<(), ApplicationError> {
-    let ((output, duration), mut console) = try_join!(
-        async {
-            let start_time = Instant::now();
-            let output = context
-                .application()
-                .command_runner()
-                .run(rule.command())
-                .await?;
+async fn run_rule(
+    context: &RunContext,
+    build: &Build,
+    rule: &Rule,
+) -> Result<(), ApplicationError> {
+    {
+        let mut console = context.application().console().lock().await;
 
-            Ok::<_, ApplicationError>((output, Instant::now() - start_time))
-        },
-        async {
-            let mut console = context.application().console().lock().await;
+        console.start_job(&job(context, build)).await?;
+        debug!(context, console, "command: {}", rule.command());
+    }
 
-            if let Some(description) = rule.description() {
-                console.write_stderr(description.as_bytes()).await?;
-                console.write_stderr(b"\n").await?;
-            }
+    let mut slice = start_build_slice(context, "job", build);
 
-            debug!(context, console, "command: {}", rule.command());
+    if let Some(description) = rule.description() {
+        slice = slice.map(|slice| slice.with_argument("description", description));
+    }
 
-            Ok(console)
-        }
-    )?;
+    let start_time = Instant::now();
+    let mut output = context
+        .application()
+        .command_runner()
+        .run(rule.command())
+        .await?;
+    let duration = Instant::now() - start_time;
+    drop(slice);
 
-    profile!(context, console, "duration: {}ms", duration.as_millis());
+    if context.options().map_sources {
+        let source_map = context.configuration().source_map();
+
+        output.stdout = source_map::rewrite_paths(source_map, &output.stdout);
+        output.stderr = source_map::rewrite_paths(source_map, &output.stderr);
+    }
+
+    context.summary().lock().unwrap().run_job(
+        build.outputs()[0].clone(),
+        duration,
+        output.status.success(),
+    );
+
+    if context.options().junit.is_some() {
+        context.junit_report().lock().unwrap().run_job(
+            job_name(build),
+            rule.name(),
+            duration,
+            output.status.code(),
+            &output.stderr,
+        );
+    }
 
-    console.write_stdout(&output.stdout).await?;
-    console.write_stderr(&output.stderr).await?;
+    write_log(context, build, &output).await?;
+
+    let mut console = context.application().console().lock().await;
+
+    console
+        .finish_job(&job(context, build), &output, duration)
+        .await?;
+    profile!(context, console, "duration: {}ms", duration.as_millis());
 
     if !output.status.success() {
         debug!(
@@ -334,12 +583,87 @@ async fn run_rule(context: &RunContext, rule: &Rule) -> Result<(), ApplicationEr
                 .unwrap_or_else(|| "-".into())
         );
 
+        context.application().database().set_failure(build.id())?;
+
         return Err(ApplicationError::Build);
     }
 
+    context
+        .application()
+        .database()
+        .set_duration(build.id(), duration)?;
+
+    Ok(())
+}
+
+// Saves stdout and stderr of a job to replay them later.
+async fn write_log(
+    context: &RunContext,
+    build: &Build,
+    output: &Output,
+) -> Result<(), ApplicationError> {
+    let database = context.application().database();
+    let file_system = context.application().file_system();
+
+    // Logs of jobs without output are removed rather than kept stale.
+    if output.stdout.is_empty() && output.stderr.is_empty() {
+        let log = database.get_log(build.id())?;
+
+        if let Some((stdout, stderr)) = log {
+            file_system.remove_file(stdout.as_ref()).await?;
+            file_system.remove_file(stderr.as_ref()).await?;
+            database.remove_log(build.id())?;
+        }
+
+        return Ok(());
+    }
+
+    let [stdout, stderr] = ["stdout", "stderr"].map(|name| {
+        context
+            .options()
+            .log_directory
+            .join(format!("{}.{name}.log", build.id()))
+    });
+
+    file_system.write_file(&stdout, &output.stdout).await?;
+    file_system.write_file(&stderr, &output.stderr).await?;
+    database.set_log(
+        build.id(),
+        &stdout.display().to_string(),
+        &stderr.display().to_string(),
+    )?;
+
     Ok(())
 }
 
+// Reads stdout and stderr of a last job. Logs removed externally are ignored
+// because they are only for replay.
+async fn read_log(
+    context: &RunContext,
+    build: &Build,
+) -> Result<Option<(Vec<u8>, Vec<u8>)>, ApplicationError> {
+    let Some((stdout_path, stderr_path)) = context.application().database().get_log(build.id())?
+    else {
+        return Ok(None);
+    };
+    let mut stdout = vec![];
+    let mut stderr = vec![];
+
+    for (path, buffer) in [(stdout_path, &mut stdout), (stderr_path, &mut stderr)] {
+        if context
+            .application()
+            .file_system()
+            .read_file(path.as_ref(), buffer)
+            .await
+            .is_err()
+        {
+            return Ok(None);
+        }
+    }
+
+    Ok(Some((stdout, stderr)))
+}
+
 fn map_build_graph_error(context: &RunContext, error: &BuildGraphError) -> ApplicationError {
     match error {
         BuildGraphError::CircularDependency(outputs) => {
@@ -1,11 +1,11 @@
-use super::{BuildFuture, options::Options};
+use super::{BuildFuture, junit::JUnitReport, options::Options, summary::Summary};
 use crate::{
     build_graph::BuildGraph,
     context::Context as ApplicationContext,
     ir::{BuildId, Configuration},
 };
 use dashmap::DashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as SyncMutex};
 use tokio::sync::Mutex;
 
 pub struct Context {
@@ -13,6 +13,10 @@ pub struct Context {
     configuration: Arc<Configuration>,
     build_futures: DashMap<BuildId, BuildFuture>,
     build_graph: Mutex<BuildGraph>,
+    // Timestamp hashes of builds estimated clean before running any jobs
+    timestamp_hashes: DashMap<BuildId, u64>,
+    summary: SyncMutex<Summary>,
+    junit_report: SyncMutex<JUnitReport>,
     options: Options,
 }
 
@@ -28,6 +32,9 @@ impl Context {
             build_graph: build_graph.into(),
             configuration,
             build_futures: DashMap::new(),
+            timestamp_hashes: DashMap::new(),
+            summary: Summary::new().into(),
+            junit_report: JUnitReport::new().into(),
             options,
         }
     }
@@ -48,6 +55,18 @@ impl Context {
         &self.build_graph
     }
 
+    pub fn timestamp_hashes(&self) -> &DashMap<BuildId, u64> {
+        &self.timestamp_hashes
+    }
+
+    pub fn summary(&self) -> &SyncMutex<Summary> {
+        &self.summary
+    }
+
+    pub fn junit_report(&self) -> &SyncMutex<JUnitReport> {
+        &self.junit_report
+    }
+
     pub fn options(&self) -> &Options {
         &self.options
     }
@@ -0,0 +1,115 @@
+use super::{check_outputs, context::Context, hash, partition_inputs};
+use crate::{
+    error::ApplicationError,
+    hash_type::HashType,
+    ir::{Build, BuildId},
+};
+use std::{collections::HashSet, sync::Arc};
+
+// Estimates builds with rules to run before running any of them. Builds are
+// dirty if their outputs are missing, their timestamp hashes are changed, or
+// any of their inputs are dirty.
+pub async fn find_dirty_builds(
+    context: &Context,
+    builds: &[&Arc<Build>],
+) -> Result<Vec<BuildId>, ApplicationError> {
+    let mut dirty_builds = HashSet::new();
+    let mut jobs = vec![];
+
+    for build in find_reachable_builds(context, builds).await? {
+        if is_dirty(context, build, &dirty_builds).await? {
+            dirty_builds.insert(build.id());
+
+            if build.rule().is_some() {
+                jobs.push(build.id());
+            }
+        }
+    }
+
+    Ok(jobs)
+}
+
+// Finds builds with rules reachable from given ones as an upper bound of
+// dirty builds without touching file systems.
+pub async fn find_reachable_jobs(
+    context: &Context,
+    builds: &[&Arc<Build>],
+) -> Result<Vec<BuildId>, ApplicationError> {
+    Ok(find_reachable_builds(context, builds)
+        .await?
+        .into_iter()
+        .filter(|build| build.rule().is_some())
+        .map(|build| build.id())
+        .collect())
+}
+
+// Finds builds reachable from given ones in a topological order.
+async fn find_reachable_builds<'a>(
+    context: &'a Context,
+    builds: &[&Arc<Build>],
+) -> Result<Vec<&'a Arc<Build>>, ApplicationError> {
+    let graph = context.build_graph().lock().await;
+    let reachable = graph.reachable(builds.iter().map(|build| build.outputs()[0].as_ref()));
+
+    Ok(graph
+        .sort()?
+        .into_iter()
+        .filter(|output| reachable.contains(output))
+        .filter_map(|output| {
+            context
+                .configuration()
+                .outputs()
+                .get(&output)
+                .filter(|build| output == build.outputs()[0])
+        })
+        .collect())
+}
+
+async fn is_dirty(
+    context: &Context,
+    build: &Build,
+    dirty_builds: &HashSet<BuildId>,
+) -> Result<bool, ApplicationError> {
+    let dynamic_inputs = context
+        .application()
+        .database()
+        .get_dynamic_inputs(build.id())?
+        .unwrap_or_default();
+
+    if build
+        .inputs()
+        .iter()
+        .chain(build.implicit_inputs())
+        .map(AsRef::as_ref)
+        .chain(dynamic_inputs.iter().map(String::as_str))
+        .filter_map(|input| context.configuration().outputs().get(input))
+        .any(|build| dirty_builds.contains(&build.id()))
+        || build.rule().is_some() && !check_outputs(context, build).await
+    {
+        return Ok(true);
+    }
+
+    let (file_inputs, phony_inputs) =
+        partition_inputs(context, build, dynamic_inputs.iter().map(String::as_str));
+
+    Ok(
+        match hash::calculate_timestamp_hash(context, build, &file_inputs, &phony_inputs).await {
+            Ok(hash) => {
+                if Some(hash)
+                    == context
+                        .application()
+                        .database()
+                        .get_hash(HashType::Timestamp, build.id())?
+                {
+                    // Reuse hashes of clean builds in their runs.
+                    context.timestamp_hashes().insert(build.id(), hash);
+                    false
+                } else {
+                    true
+                }
+            }
+            // Let builds report errors.
+            Err(_) => true,
+        },
+    )
+}
@@ -0,0 +1,197 @@
+use std::{fmt::Write, time::Duration};
+
+const SUITE_NAME: &str = "turtle";
+
+#[derive(Debug)]
+enum Outcome {
+    Success,
+    Failure {
+        exit_status: Option<i32>,
+        stderr: String,
+    },
+    Skipped,
+}
+
+#[derive(Debug)]
+struct TestCase {
+    name: String,
+    class_name: String,
+    duration: Duration,
+    outcome: Outcome,
+}
+
+// A JUnit XML report where jobs are test cases
+#[derive(Debug, Default)]
+pub struct JUnitReport {
+    cases: Vec<TestCase>,
+}
+
+impl JUnitReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn run_job(
+        &mut self,
+        name: &str,
+        rule: &str,
+        duration: Duration,
+        exit_status: Option<i32>,
+        stderr: &[u8],
+    ) {
+        self.cases.push(TestCase {
+            name: name.into(),
+            class_name: rule.into(),
+            duration,
+            outcome: if exit_status == Some(0) {
+                Outcome::Success
+            } else {
+                Outcome::Failure {
+                    exit_status,
+                    stderr: String::from_utf8_lossy(stderr).into(),
+                }
+            },
+        });
+    }
+
+    pub fn skip_job(&mut self, name: &str, rule: &str) {
+        self.cases.push(TestCase {
+            name: name.into(),
+            class_name: rule.into(),
+            duration: Duration::default(),
+            outcome: Outcome::Skipped,
+        });
+    }
+
+    pub fn to_xml(&self) -> String {
+        let count = |predicate: fn(&Outcome) -> bool| {
+            self.cases
+                .iter()
+                .filter(|case| predicate(&case.outcome))
+                .count()
+        };
+        let mut string = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n".to_owned();
+
+        writeln!(
+            string,
+            "  <testsuite name=\"{SUITE_NAME}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">",
+            self.cases.len(),
+            count(|outcome| matches!(outcome, Outcome::Failure { .. })),
+            count(|outcome| matches!(outcome, Outcome::Skipped)),
+            self.cases
+                .iter()
+                .map(|case| case.duration)
+                .sum::<Duration>()
+                .as_secs_f64(),
+        )
+        .unwrap();
+
+        for case in &self.cases {
+            write!(
+                string,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                escape(&case.name),
+                escape(&case.class_name),
+                case.duration.as_secs_f64()
+            )
+            .unwrap();
+
+            match &case.outcome {
+                Outcome::Success => string.push_str("/>\n"),
+                Outcome::Failure {
+                    exit_status,
+                    stderr,
+                } => writeln!(
+                    string,
+                    ">\n      <failure message=\"exit status {}\">{}</failure>\n    </testcase>",
+                    exit_status
+                        .map(|status| status.to_string())
+                        .unwrap_or_else(|| "-".into()),
+                    escape(stderr)
+                )
+                .unwrap(),
+                Outcome::Skipped => string.push_str(">\n      <skipped/>\n    </testcase>\n"),
+            }
+        }
+
+        string.push_str("  </testsuite>\n</testsuites>\n");
+
+        string
+    }
+}
+
+fn escape(string: &str) -> String {
+    string
+        .chars()
+        // Remove characters invalid in XML.
+        .filter(|&character| !character.is_control() || matches!(character, '\t' | '\n' | '\r'))
+        .fold(String::new(), |mut string, character| {
+            match character {
+                '&' => string.push_str("&amp;"),
+                '<' => string.push_str("&lt;"),
+                '>' => string.push_str("&gt;"),
+                '"' => string.push_str("&quot;"),
+                '\'' => string.push_str("&apos;"),
+                character => string.push(character),
+            }
+
+            string
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn write_empty_report() {
+        assert_eq!(
+            JUnitReport::new().to_xml(),
+            [
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>",
+                "<testsuites>",
+                "  <testsuite name=\"turtle\" tests=\"0\" failures=\"0\" skipped=\"0\" time=\"0.000\">",
+                "  </testsuite>",
+                "</testsuites>",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn write_report() {
+        let mut report = JUnitReport::new();
+
+        report.run_job("cc foo.o", "cc", Duration::from_millis(1500), Some(0), b"");
+        report.run_job(
+            "bar.o",
+            "cc",
+            Duration::from_millis(500),
+            Some(1),
+            b"a < b\x1b",
+        );
+        report.skip_job("baz", "ld");
+
+        assert_eq!(
+            report.to_xml(),
+            [
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>",
+                "<testsuites>",
+                "  <testsuite name=\"turtle\" tests=\"3\" failures=\"1\" skipped=\"1\" time=\"2.000\">",
+                "    <testcase name=\"cc foo.o\" classname=\"cc\" time=\"1.500\"/>",
+                "    <testcase name=\"bar.o\" classname=\"cc\" time=\"0.500\">",
+                "      <failure message=\"exit status 1\">a &lt; b</failure>",
+                "    </testcase>",
+                "    <testcase name=\"baz\" classname=\"ld\" time=\"0.000\">",
+                "      <skipped/>",
+                "    </testcase>",
+                "  </testsuite>",
+                "</testsuites>",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -20,8 +20,7 @@ macro_rules! profile {
 macro_rules! log {
     ($console:expr, $template:literal, $($value:expr),+) => {
         $console.write_stderr(
-            ("turtle: ".to_owned() + &format!($template, $($value),+)).as_bytes(),
+            ("turtle: ".to_owned() + &format!($template, $($value),+) + "\n").as_bytes(),
         ).await?;
-        $console.write_stderr("\n".as_bytes()).await?;
     };
 }
@@ -1,5 +1,12 @@
+use std::path::PathBuf;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Options {
+    pub verbose: bool,
     pub debug: bool,
     pub profile: bool,
+    pub summary: bool,
+    pub map_sources: bool,
+    pub junit: Option<String>,
+    pub log_directory: PathBuf,
 }
@@ -0,0 +1,55 @@
+use once_cell::sync::Lazy;
+use regex::bytes::{Captures, Regex};
+use std::{collections::HashMap, sync::Arc};
+
+// Paths delimited by whitespaces, quotes, brackets, or separators of compiler
+// diagnostics like `foo.c:1:2:`
+static PATH_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r#"[^\s:;,='"`()<>\[\]{}]+"#).unwrap());
+
+// Rewrites outputs in job outputs to their source files.
+pub fn rewrite_paths(source_map: &HashMap<Arc<str>, Arc<str>>, text: &[u8]) -> Vec<u8> {
+    PATH_PATTERN
+        .replace_all(text, |captures: &Captures| {
+            str::from_utf8(&captures[0])
+                .ok()
+                .and_then(|path| source_map.get(path))
+                .map(|source| source.as_bytes().to_vec())
+                .unwrap_or_else(|| captures[0].to_vec())
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source_map() -> HashMap<Arc<str>, Arc<str>> {
+        [("out/foo.c".into(), "foo.rs".into())]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn rewrite_nothing() {
+        assert_eq!(
+            rewrite_paths(&source_map(), b"foo.c: error"),
+            b"foo.c: error"
+        );
+    }
+
+    #[test]
+    fn rewrite_output() {
+        assert_eq!(
+            rewrite_paths(&source_map(), b"out/foo.c:1:2: error: 'out/foo.c'\n"),
+            b"foo.rs:1:2: error: 'foo.rs'\n"
+        );
+    }
+
+    #[test]
+    fn rewrite_no_partial_path() {
+        assert_eq!(
+            rewrite_paths(&source_map(), b"out/foo.cc xout/foo.c"),
+            b"out/foo.cc xout/foo.c"
+        );
+    }
+}
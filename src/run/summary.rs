@@ -0,0 +1,103 @@
+use crate::hash_type::HashType;
+use itertools::Itertools;
+use std::{fmt::Write, sync::Arc, time::Duration};
+
+const SLOWEST_JOB_COUNT: usize = 5;
+
+// Statistics of jobs in a build
+#[derive(Debug, Default)]
+pub struct Summary {
+    // Primary outputs and durations of jobs run
+    jobs: Vec<(Arc<str>, Duration)>,
+    timestamp_skipped: usize,
+    content_skipped: usize,
+    failed: usize,
+}
+
+impl Summary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn run_job(&mut self, output: Arc<str>, duration: Duration, success: bool) {
+        self.jobs.push((output, duration));
+
+        if !success {
+            self.failed += 1;
+        }
+    }
+
+    pub fn skip_job(&mut self, r#type: HashType) {
+        match r#type {
+            HashType::Content => self.content_skipped += 1,
+            HashType::Timestamp => self.timestamp_skipped += 1,
+        }
+    }
+
+    pub fn format(&self, wall_time: Duration) -> String {
+        let mut string = format!(
+            "{} ran, {} skipped by timestamp hash, {} skipped by content hash, {} failed in {:.2}s",
+            self.jobs.len() - self.failed,
+            self.timestamp_skipped,
+            self.content_skipped,
+            self.failed,
+            wall_time.as_secs_f64()
+        );
+
+        for (output, duration) in self
+            .jobs
+            .iter()
+            .sorted_by(|(one, one_duration), (other, other_duration)| {
+                other_duration.cmp(one_duration).then(one.cmp(other))
+            })
+            .take(SLOWEST_JOB_COUNT)
+        {
+            write!(string, "\n  {:.2}s {output}", duration.as_secs_f64()).unwrap();
+        }
+
+        string
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_empty() {
+        assert_eq!(
+            Summary::new().format(Duration::from_millis(1234)),
+            "0 ran, 0 skipped by timestamp hash, 0 skipped by content hash, 0 failed in 1.23s"
+        );
+    }
+
+    #[test]
+    fn format_jobs() {
+        let mut summary = Summary::new();
+
+        for (index, milliseconds) in [10, 70, 30, 50, 20, 60].into_iter().enumerate() {
+            summary.run_job(
+                format!("{index}").into(),
+                Duration::from_millis(milliseconds),
+                index != 1,
+            );
+        }
+
+        summary.skip_job(HashType::Timestamp);
+        summary.skip_job(HashType::Timestamp);
+        summary.skip_job(HashType::Content);
+
+        assert_eq!(
+            summary.format(Duration::from_secs(1)),
+            [
+                "5 ran, 2 skipped by timestamp hash, 1 skipped by content hash, 1 failed in 1.00s",
+                "  0.07s 1",
+                "  0.06s 5",
+                "  0.05s 3",
+                "  0.03s 2",
+                "  0.02s 4",
+            ]
+            .join("\n")
+        );
+    }
+}
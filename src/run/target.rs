@@ -0,0 +1,366 @@
+use crate::{
+    compile::PHONY_RULE,
+    error::ApplicationError,
+    ir::{Build, Configuration},
+};
+use itertools::Itertools;
+use regex::Regex;
+use std::{collections::HashMap, sync::Arc};
+
+const SOURCE_SUFFIX: char = '^';
+const DIRECTORY_SUFFIX: char = '/';
+const GLOB_CHARACTERS: &[char] = &['*', '?', '['];
+
+// Resolves targets into builds. Targets are outputs, sources with `^`
+// suffixes, directory prefixes, or glob patterns. Builds are filtered by a
+// rule if any. If no target is given with a rule, all outputs are targets.
+pub fn resolve_targets<'a>(
+    configuration: &'a Configuration,
+    targets: &[String],
+    rule: Option<&str>,
+) -> Result<Vec<&'a Arc<Build>>, ApplicationError> {
+    // Build a reverse input index only when sources are specified.
+    let dependents = targets
+        .iter()
+        .any(|target| {
+            target.ends_with(SOURCE_SUFFIX)
+                && !configuration.outputs().contains_key(target.as_str())
+        })
+        .then(|| build_dependents(configuration));
+    let mut builds = vec![];
+
+    if targets.is_empty() {
+        builds.extend(configuration.outputs().values());
+    }
+
+    for target in targets {
+        // Outputs ending with `^` take precedence over sources.
+        if let Some(build) = configuration.outputs().get(target.as_str()) {
+            builds.push(build);
+        } else if let (Some(source), Some(dependents)) =
+            (target.strip_suffix(SOURCE_SUFFIX), &dependents)
+        {
+            builds.push(
+                match dependents
+                    .get(source)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default()
+                {
+                    [] => return Err(ApplicationError::OutputNotFound(target.clone())),
+                    [build] => *build,
+                    builds => {
+                        return Err(ApplicationError::AmbiguousSource(
+                            source.into(),
+                            builds
+                                .iter()
+                                .map(|build| build.outputs()[0].clone())
+                                .sorted()
+                                .collect(),
+                        ));
+                    }
+                },
+            );
+        } else if target.ends_with(DIRECTORY_SUFFIX) || target.contains(GLOB_CHARACTERS) {
+            let pattern = compile_pattern(target)?;
+            let matches = configuration
+                .outputs()
+                .iter()
+                .filter(|(output, _)| pattern.is_match(output))
+                .sorted_by(|(one, _), (other, _)| one.cmp(other))
+                .map(|(_, build)| build)
+                .collect::<Vec<_>>();
+
+            if matches.is_empty() {
+                return Err(ApplicationError::OutputNotFound(target.clone()));
+            }
+
+            builds.extend(matches);
+        } else {
+            return Err(ApplicationError::OutputNotFound(target.clone()));
+        }
+    }
+
+    let builds = builds
+        .into_iter()
+        .filter(|build| {
+            rule.is_none_or(|rule| {
+                build.rule().map(|rule| rule.name()).unwrap_or(PHONY_RULE) == rule
+            })
+        })
+        .unique_by(|build| build.id())
+        .collect::<Vec<_>>();
+
+    if let Some(rule) = rule
+        && builds.is_empty()
+    {
+        return Err(ApplicationError::RuleOutputNotFound(rule.into()));
+    }
+
+    Ok(builds)
+}
+
+// Compiles a directory prefix or glob pattern into a regular expression where
+// `**` matches any number of directories.
+fn compile_pattern(pattern: &str) -> Result<Regex, ApplicationError> {
+    let mut regex = "^".to_owned();
+    let mut characters = pattern.chars().peekable();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '*' if characters.peek() == Some(&'*') => {
+                characters.next();
+
+                if characters.peek() == Some(&'/') {
+                    characters.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                regex.push('[');
+
+                if characters.next_if_eq(&'!').is_some() {
+                    regex.push('^');
+                }
+
+                for character in characters.by_ref() {
+                    if character == ']' {
+                        break;
+                    } else if matches!(character, '\\' | '[') {
+                        regex.push('\\');
+                    }
+
+                    regex.push(character);
+                }
+
+                regex.push(']');
+            }
+            character => regex.push_str(&regex::escape(&character.to_string())),
+        }
+    }
+
+    if !pattern.ends_with(DIRECTORY_SUFFIX) {
+        regex.push('$');
+    }
+
+    Regex::new(&regex).map_err(|_| ApplicationError::InvalidTargetPattern(pattern.into()))
+}
+
+fn build_dependents(configuration: &Configuration) -> HashMap<&str, Vec<&Arc<Build>>> {
+    let mut dependents = HashMap::<_, Vec<_>>::new();
+
+    for build in configuration
+        .outputs()
+        .values()
+        .unique_by(|build| build.id())
+    {
+        for input in build
+            .inputs()
+            .iter()
+            .chain(build.implicit_inputs())
+            .chain(build.order_only_inputs())
+            .unique()
+        {
+            dependents.entry(input.as_ref()).or_default().push(build);
+        }
+    }
+
+    dependents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Rule;
+    use std::collections::HashSet;
+
+    fn configuration(builds: &[(&str, &str, &[&str])]) -> Configuration {
+        Configuration::new(
+            builds
+                .iter()
+                .map(|(output, rule, inputs)| {
+                    (
+                        (*output).into(),
+                        Build::new(
+                            vec![(*output).into()],
+                            vec![],
+                            Some(Rule::new(*rule, *rule, None, false)),
+                            inputs.iter().map(|&input| input.into()).collect(),
+                            vec![],
+                            vec![],
+                            None,
+                        )
+                        .into(),
+                    )
+                })
+                .collect(),
+            HashSet::new(),
+            Default::default(),
+            Default::default(),
+            None,
+        )
+    }
+
+    fn resolve_outputs(
+        configuration: &Configuration,
+        targets: &[&str],
+        rule: Option<&str>,
+    ) -> Result<Vec<Arc<str>>, ApplicationError> {
+        Ok(resolve_targets(
+            configuration,
+            &targets
+                .iter()
+                .map(|&target| target.into())
+                .collect::<Vec<_>>(),
+            rule,
+        )?
+        .into_iter()
+        .map(|build| build.outputs()[0].clone())
+        .collect())
+    }
+
+    #[test]
+    fn resolve_output() {
+        let configuration = configuration(&[("foo.o", "cc", &["foo.c"])]);
+
+        assert_eq!(
+            resolve_outputs(&configuration, &["foo.o"], None).unwrap(),
+            ["foo.o".into()]
+        );
+    }
+
+    #[test]
+    fn fail_to_resolve_unknown_output() {
+        let configuration = configuration(&[("foo.o", "cc", &["foo.c"])]);
+
+        assert_eq!(
+            resolve_outputs(&configuration, &["bar.o"], None).unwrap_err(),
+            ApplicationError::OutputNotFound("bar.o".into())
+        );
+    }
+
+    #[test]
+    fn resolve_source() {
+        let configuration =
+            configuration(&[("foo.o", "cc", &["foo.c"]), ("bar.o", "cc", &["bar.c"])]);
+
+        assert_eq!(
+            resolve_outputs(&configuration, &["foo.c^"], None).unwrap(),
+            ["foo.o".into()]
+        );
+    }
+
+    #[test]
+    fn resolve_output_with_source_suffix() {
+        let configuration =
+            configuration(&[("foo.c^", "cc", &["bar.c"]), ("foo.o", "cc", &["foo.c"])]);
+
+        assert_eq!(
+            resolve_outputs(&configuration, &["foo.c^"], None).unwrap(),
+            ["foo.c^".into()]
+        );
+    }
+
+    #[test]
+    fn fail_to_resolve_unknown_source() {
+        let configuration = configuration(&[("foo.o", "cc", &["foo.c"])]);
+
+        assert_eq!(
+            resolve_outputs(&configuration, &["bar.c^"], None).unwrap_err(),
+            ApplicationError::OutputNotFound("bar.c^".into())
+        );
+    }
+
+    #[test]
+    fn fail_to_resolve_ambiguous_source() {
+        let configuration =
+            configuration(&[("foo.o", "cc", &["foo.h"]), ("bar.o", "cc", &["foo.h"])]);
+
+        assert_eq!(
+            resolve_outputs(&configuration, &["foo.h^"], None).unwrap_err(),
+            ApplicationError::AmbiguousSource("foo.h".into(), vec!["bar.o".into(), "foo.o".into()])
+        );
+    }
+
+    #[test]
+    fn resolve_glob_pattern() {
+        let configuration = configuration(&[
+            ("out/foo.o", "cc", &[]),
+            ("out/bar/baz.o", "cc", &[]),
+            ("out/bar/baz.a", "ar", &[]),
+            ("foo.o", "cc", &[]),
+        ]);
+
+        assert_eq!(
+            resolve_outputs(&configuration, &["out/**/*.o"], None).unwrap(),
+            ["out/bar/baz.o".into(), "out/foo.o".into()]
+        );
+        assert_eq!(
+            resolve_outputs(&configuration, &["out/*.o"], None).unwrap(),
+            ["out/foo.o".into()]
+        );
+        assert_eq!(
+            resolve_outputs(&configuration, &["out/bar/baz.[!o]"], None).unwrap(),
+            ["out/bar/baz.a".into()]
+        );
+    }
+
+    #[test]
+    fn fail_to_resolve_unmatched_glob_pattern() {
+        let configuration = configuration(&[("foo.o", "cc", &[])]);
+
+        assert_eq!(
+            resolve_outputs(&configuration, &["*.a"], None).unwrap_err(),
+            ApplicationError::OutputNotFound("*.a".into())
+        );
+    }
+
+    #[test]
+    fn resolve_directory() {
+        let configuration = configuration(&[
+            ("out/pkg/foo.o", "cc", &[]),
+            ("out/pkg/bar/baz.o", "cc", &[]),
+            ("out/package.o", "cc", &[]),
+        ]);
+
+        assert_eq!(
+            resolve_outputs(&configuration, &["out/pkg/"], None).unwrap(),
+            ["out/pkg/bar/baz.o".into(), "out/pkg/foo.o".into()]
+        );
+    }
+
+    #[test]
+    fn resolve_rule() {
+        let configuration = configuration(&[
+            ("foo.o", "cc", &[]),
+            ("bar.o", "cc", &[]),
+            ("foo.a", "ar", &[]),
+        ]);
+
+        assert_eq!(
+            resolve_outputs(&configuration, &[], Some("cc"))
+                .unwrap()
+                .into_iter()
+                .sorted()
+                .collect::<Vec<_>>(),
+            ["bar.o".into(), "foo.o".into()]
+        );
+        assert_eq!(
+            resolve_outputs(&configuration, &["foo.*"], Some("ar")).unwrap(),
+            ["foo.a".into()]
+        );
+    }
+
+    #[test]
+    fn fail_to_resolve_rule_without_outputs() {
+        let configuration = configuration(&[("foo.o", "cc", &[])]);
+
+        assert_eq!(
+            resolve_outputs(&configuration, &[], Some("ar")).unwrap_err(),
+            ApplicationError::RuleOutputNotFound("ar".into())
+        );
+    }
+}
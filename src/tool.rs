@@ -1,3 +1,120 @@
+mod affected;
+mod browse;
 mod clean_dead;
+mod commands;
+mod compilation_database;
+mod dynamic_configuration;
+mod format;
+mod graph;
+mod inputs;
+mod last_failures;
+mod lint;
+mod log;
+mod missing_dependencies;
+mod outputs;
+mod query;
+mod rules;
+mod targets;
 
+pub use affected::*;
+pub use browse::*;
 pub use clean_dead::*;
+pub use commands::*;
+pub use compilation_database::*;
+pub use format::*;
+pub use graph::*;
+pub use inputs::*;
+pub use last_failures::*;
+pub use lint::*;
+pub use log::*;
+pub use missing_dependencies::*;
+pub use outputs::*;
+pub use query::*;
+pub use rules::*;
+pub use targets::*;
+
+use crate::{
+    build_graph::BuildGraph,
+    context::Context,
+    error::ApplicationError,
+    ir::{Build, Configuration},
+};
+use itertools::Itertools;
+use std::sync::Arc;
+
+const PHONY_RULE: &str = "phony";
+
+fn rule_name(build: &Build) -> &str {
+    build.rule().map(|rule| rule.name()).unwrap_or(PHONY_RULE)
+}
+
+fn build_inputs(build: &Build) -> impl Iterator<Item = &Arc<str>> {
+    build
+        .inputs()
+        .iter()
+        .chain(build.implicit_inputs())
+        .chain(build.order_only_inputs())
+}
+
+// Outputs of builds that list a given path as an input
+fn build_dependents<'a>(
+    configuration: &'a Configuration,
+    graph: &BuildGraph,
+    path: &str,
+) -> Vec<&'a Arc<str>> {
+    graph
+        .dependents(path)
+        .iter()
+        .filter_map(|dependent| configuration.outputs().get(dependent))
+        .filter(|build| build_inputs(build).any(|input| input.as_ref() == path))
+        .unique_by(|build| build.id())
+        .flat_map(|build| build.outputs())
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+async fn write_stdout(context: &Context, buffer: impl AsRef<[u8]>) -> Result<(), ApplicationError> {
+    context
+        .console()
+        .lock()
+        .await
+        .write_stdout(buffer.as_ref())
+        .await?;
+
+    Ok(())
+}
+
+async fn write_stderr(context: &Context, buffer: impl AsRef<[u8]>) -> Result<(), ApplicationError> {
+    context
+        .console()
+        .lock()
+        .await
+        .write_stderr(buffer.as_ref())
+        .await?;
+
+    Ok(())
+}
+
+async fn write_lines(
+    context: &Context,
+    lines: impl IntoIterator<Item = impl AsRef<str>>,
+) -> Result<(), ApplicationError> {
+    write_paths(context, lines, false).await
+}
+
+// Writes paths separated by newlines or null characters.
+async fn write_paths(
+    context: &Context,
+    paths: impl IntoIterator<Item = impl AsRef<str>>,
+    null: bool,
+) -> Result<(), ApplicationError> {
+    let mut string = String::new();
+
+    for path in paths {
+        string.push_str(path.as_ref());
+        string.push(if null { '\0' } else { '\n' });
+    }
+
+    write_stdout(context, string).await
+}
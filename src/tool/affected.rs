@@ -0,0 +1,50 @@
+use super::{dynamic_configuration::load_dynamic_configuration, rule_name, write_paths};
+use crate::{
+    build_graph::BuildGraph, context::Context, error::ApplicationError, ir::Configuration,
+};
+use itertools::Itertools;
+use tokio::io::{AsyncReadExt, stdin};
+
+pub async fn affected(
+    context: &Context,
+    configuration: &Configuration,
+    paths: &[String],
+    default_only: bool,
+    rule: Option<&str>,
+    null: bool,
+) -> Result<(), ApplicationError> {
+    let paths = if paths.is_empty() {
+        let mut string = String::new();
+        stdin().read_to_string(&mut string).await?;
+
+        string
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(From::from)
+            .collect()
+    } else {
+        paths.to_vec()
+    };
+
+    let mut graph = BuildGraph::new(configuration.outputs());
+    graph.insert_dynamic(&load_dynamic_configuration(context, configuration)?);
+
+    write_paths(
+        context,
+        graph
+            .reverse_reachable(paths.iter().map(String::as_str))
+            .iter()
+            .filter(|path| {
+                configuration
+                    .outputs()
+                    .get(*path)
+                    .map(|build| rule.map(|rule| rule_name(build) == rule).unwrap_or(true))
+                    .unwrap_or_default()
+                    && (!default_only || configuration.default_outputs().contains(*path))
+            })
+            .sorted(),
+        null,
+    )
+    .await
+}
@@ -0,0 +1,515 @@
+use super::{
+    build_dependents, build_inputs, dynamic_configuration::load_dynamic_configuration, rule_name,
+    write_stderr,
+};
+use crate::{
+    build_graph::BuildGraph,
+    context::Context,
+    error::ApplicationError,
+    hash_type::HashType,
+    ir::{Configuration, DynamicConfiguration},
+};
+use futures::{StreamExt, stream::FuturesUnordered};
+use itertools::Itertools;
+use std::{collections::HashSet, fmt::Write, net::Ipv4Addr, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    select,
+    time::timeout,
+};
+
+const DEFAULT_PORT: u16 = 8000;
+const MAX_REQUEST_SIZE: usize = 1 << 16;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const TARGET_PARAMETER: &str = "target";
+const STYLE: &str =
+    "body{font-family:sans-serif;margin:2em}h2{font-size:1.1em}ul{padding-left:1.5em}";
+
+struct Page {
+    status: &'static str,
+    title: String,
+    body: String,
+}
+
+pub async fn browse(
+    context: &Context,
+    configuration: &Configuration,
+    outputs: &[String],
+    port: Option<u16>,
+) -> Result<(), ApplicationError> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port.unwrap_or(DEFAULT_PORT))).await?;
+
+    write_stderr(
+        context,
+        format!(
+            "turtle: serving at http://{}/{}\n",
+            listener.local_addr()?,
+            outputs
+                .first()
+                .map(|output| format!("?{}={}", TARGET_PARAMETER, encode_url(output)))
+                .unwrap_or_default()
+        ),
+    )
+    .await?;
+
+    listen(context, configuration, listener).await
+}
+
+async fn listen(
+    context: &Context,
+    configuration: &Configuration,
+    listener: TcpListener,
+) -> Result<(), ApplicationError> {
+    let graph = BuildGraph::new(configuration.outputs());
+    let dynamic_configuration = load_dynamic_configuration(context, configuration)?;
+    let mut connections = FuturesUnordered::new();
+
+    // Connections are served concurrently so that slow clients do not block
+    // others.
+    loop {
+        select! {
+            result = listener.accept() => {
+                let (stream, _) = result?;
+
+                connections.push(serve(
+                    context,
+                    configuration,
+                    &dynamic_configuration,
+                    &graph,
+                    stream,
+                ));
+            }
+            Some(result) = connections.next() => {
+                // Failures of individual connections do not stop the server.
+                if let Err(error) = result {
+                    write_stderr(context, format!("turtle: {error}\n")).await?;
+                }
+            }
+        }
+    }
+}
+
+async fn serve(
+    context: &Context,
+    configuration: &Configuration,
+    dynamic_configuration: &DynamicConfiguration,
+    graph: &BuildGraph,
+    mut stream: TcpStream,
+) -> Result<(), ApplicationError> {
+    let request = timeout(REQUEST_TIMEOUT, read_request(&mut stream))
+        .await
+        .map_err(|_| ApplicationError::Other("request timed out".into()))??;
+
+    let request = String::from_utf8_lossy(&request);
+    let page = match request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["GET", target, ..] => {
+            let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+            if path != "/" {
+                not_found_page(path)
+            } else if let Some(output) = query
+                .split('&')
+                .filter_map(|parameter| parameter.split_once('='))
+                .find(|(key, _)| *key == TARGET_PARAMETER)
+                .map(|(_, value)| decode_url(value))
+            {
+                target_page(
+                    context,
+                    configuration,
+                    dynamic_configuration,
+                    graph,
+                    &output,
+                )?
+            } else {
+                index_page(configuration)?
+            }
+        }
+        _ => Page {
+            status: "405 Method Not Allowed",
+            title: "method not allowed".into(),
+            body: String::new(),
+        },
+    };
+
+    stream.write_all(render_page(&page)?.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Vec<u8>, ApplicationError> {
+    let mut request = vec![];
+    let mut buffer = [0; 1 << 10];
+
+    while !request.ends_with(b"\r\n\r\n") && request.len() < MAX_REQUEST_SIZE {
+        let size = stream.read(&mut buffer).await?;
+
+        if size == 0 {
+            break;
+        }
+
+        request.extend_from_slice(&buffer[..size]);
+    }
+
+    Ok(request)
+}
+
+fn index_page(configuration: &Configuration) -> Result<Page, ApplicationError> {
+    let mut body = String::new();
+
+    if !configuration.default_outputs().is_empty() {
+        writeln!(body, "<h2>default outputs</h2>")?;
+        write_links(
+            &mut body,
+            configuration,
+            configuration.default_outputs().iter().sorted(),
+        )?;
+    }
+
+    let inputs = configuration
+        .outputs()
+        .values()
+        .flat_map(|build| build_inputs(build))
+        .collect::<HashSet<_>>();
+
+    writeln!(body, "<h2>root targets</h2>")?;
+    write_links(
+        &mut body,
+        configuration,
+        configuration
+            .outputs()
+            .keys()
+            .filter(|output| !inputs.contains(output))
+            .sorted(),
+    )?;
+
+    Ok(Page {
+        status: "200 OK",
+        title: "turtle".into(),
+        body,
+    })
+}
+
+fn target_page(
+    context: &Context,
+    configuration: &Configuration,
+    dynamic_configuration: &DynamicConfiguration,
+    graph: &BuildGraph,
+    output: &str,
+) -> Result<Page, ApplicationError> {
+    let build = configuration.outputs().get(output);
+
+    if build.is_none() && !graph.contains(output) {
+        return Ok(not_found_page(output));
+    }
+
+    let mut body = String::new();
+
+    if let Some(source) = configuration.source_map().get(output) {
+        writeln!(body, "<p>source: {}</p>", escape_html(source))?;
+    }
+
+    if let Some(build) = build {
+        writeln!(body, "<h2>rule</h2>")?;
+        writeln!(
+            body,
+            "<p>{}{}</p>",
+            escape_html(rule_name(build)),
+            build
+                .rule()
+                .and_then(|rule| rule.description())
+                .map(|description| format!(": {}", escape_html(description)))
+                .unwrap_or_default()
+        )?;
+
+        for (title, inputs) in [
+            ("inputs", build.inputs()),
+            ("implicit inputs", build.implicit_inputs()),
+            ("order-only inputs", build.order_only_inputs()),
+            (
+                "dynamic inputs",
+                dynamic_configuration
+                    .outputs()
+                    .get(&build.outputs()[0])
+                    .map(|build| build.inputs())
+                    .unwrap_or_default(),
+            ),
+        ] {
+            if !inputs.is_empty() {
+                writeln!(body, "<h2>{title}</h2>")?;
+                write_links(&mut body, configuration, inputs)?;
+            }
+        }
+    }
+
+    writeln!(body, "<h2>dependents</h2>")?;
+    write_links(
+        &mut body,
+        configuration,
+        build_dependents(configuration, graph, output),
+    )?;
+
+    if let Some(build) = build {
+        writeln!(body, "<h2>last build</h2>")?;
+        writeln!(body, "<dl>")?;
+
+        for (name, r#type) in [
+            ("timestamp hash", HashType::Timestamp),
+            ("content hash", HashType::Content),
+        ] {
+            writeln!(
+                body,
+                "<dt>{}</dt><dd>{}</dd>",
+                name,
+                context
+                    .database()
+                    .get_hash(r#type, build.id())?
+                    .map(|hash| format!("{hash:016x}"))
+                    .unwrap_or_else(|| "-".into())
+            )?;
+        }
+
+        writeln!(
+            body,
+            "<dt>duration</dt><dd>{}</dd>",
+            context
+                .database()
+                .get_duration(build.id())?
+                .map(|duration| format!("{}ms", duration.as_millis()))
+                .unwrap_or_else(|| "-".into())
+        )?;
+        writeln!(body, "</dl>")?;
+    }
+
+    Ok(Page {
+        status: "200 OK",
+        title: output.into(),
+        body,
+    })
+}
+
+fn not_found_page(target: &str) -> Page {
+    Page {
+        status: "404 Not Found",
+        title: format!("\"{target}\" not found"),
+        body: String::new(),
+    }
+}
+
+fn render_page(page: &Page) -> Result<String, ApplicationError> {
+    let mut html = String::new();
+
+    write!(
+        html,
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{title}</title>\
+         <style>{STYLE}</style></head><body><p><a href=\"/\">turtle</a></p>\
+         <h1>{title}</h1>\n{}</body></html>\n",
+        page.body,
+        title = escape_html(&page.title),
+    )?;
+
+    Ok(format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        page.status,
+        html.len(),
+        html
+    ))
+}
+
+fn write_links(
+    body: &mut String,
+    configuration: &Configuration,
+    paths: impl IntoIterator<Item = impl AsRef<str>>,
+) -> Result<(), ApplicationError> {
+    writeln!(body, "<ul>")?;
+
+    for path in paths {
+        let path = path.as_ref();
+
+        write!(
+            body,
+            "<li><a href=\"/?{}={}\">{}</a>",
+            TARGET_PARAMETER,
+            encode_url(path),
+            escape_html(path)
+        )?;
+
+        if let Some(source) = configuration.source_map().get(path) {
+            write!(body, " ({})", escape_html(source))?;
+        }
+
+        writeln!(body, "</li>")?;
+    }
+
+    writeln!(body, "</ul>")?;
+
+    Ok(())
+}
+
+fn escape_html(string: &str) -> String {
+    string
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn encode_url(string: &str) -> String {
+    string
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+                (byte as char).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
+
+fn decode_url(string: &str) -> String {
+    let mut bytes = vec![];
+    let mut iterator = string.bytes();
+
+    while let Some(byte) = iterator.next() {
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let digits = iterator.clone().take(2).collect::<Vec<_>>();
+
+                if let Some(byte) = str::from_utf8(&digits)
+                    .ok()
+                    .filter(|digits| digits.len() == 2)
+                    .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                {
+                    bytes.push(byte);
+                    iterator.nth(1);
+                } else {
+                    bytes.push(b'%');
+                }
+            }
+            byte => bytes.push(byte),
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        infrastructure::{Database, OsCommandRunner, OsConsole, OsDatabase, OsFileSystem},
+        ir::Build,
+        trace::Trace,
+    };
+    use std::{collections::HashMap, sync::Arc};
+    use tempfile::tempdir;
+
+    async fn fetch(stream: &mut TcpStream, target: &str) -> String {
+        stream
+            .write_all(format!("GET {target} HTTP/1.1\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn serve_pages() {
+        let directory = tempdir().unwrap();
+        let database = OsDatabase::new();
+        database.initialize(directory.path()).unwrap();
+        let context = Context::new(
+            OsCommandRunner::new(1),
+            Box::new(OsConsole::new(None, 1)),
+            database,
+            OsFileSystem::new(1),
+            Trace::new(false),
+        );
+        let configuration = Configuration::new(
+            [("foo", "bar"), ("bar", "baz")]
+                .into_iter()
+                .map(|(output, input)| {
+                    (
+                        output.into(),
+                        Build::new(
+                            vec![output.into()],
+                            vec![],
+                            None,
+                            vec![input.into()],
+                            vec![],
+                            vec![],
+                            None,
+                        )
+                        .into(),
+                    )
+                })
+                .collect::<HashMap<Arc<str>, Arc<Build>>>(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            None,
+        );
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        select! {
+            result = listen(&context, &configuration, listener) => panic!("{result:?}"),
+            _ = async {
+                // An idle connection does not block others.
+                let _idle = TcpStream::connect(address).await.unwrap();
+
+                let response = fetch(&mut TcpStream::connect(address).await.unwrap(), "/").await;
+
+                assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+                assert!(response.contains("<h2>root targets</h2>"));
+                assert!(response.contains("<a href=\"/?target=foo\">foo</a>"));
+                assert!(!response.contains("<a href=\"/?target=bar\">bar</a>"));
+
+                let response = fetch(
+                    &mut TcpStream::connect(address).await.unwrap(),
+                    "/?target=bar",
+                )
+                .await;
+
+                assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+                assert!(response.contains("<h1>bar</h1>"));
+                assert!(response.contains("<a href=\"/?target=baz\">baz</a>"));
+                assert!(response.contains("<a href=\"/?target=foo\">foo</a>"));
+            } => {}
+        }
+    }
+
+    #[test]
+    fn escape_html_characters() {
+        assert_eq!(escape_html("foo"), "foo");
+        assert_eq!(escape_html("<a&b>"), "&lt;a&amp;b&gt;");
+        assert_eq!(escape_html("\"foo\""), "&quot;foo&quot;");
+    }
+
+    #[test]
+    fn encode_and_decode_url() {
+        for string in ["foo", "foo/bar.o", "foo bar", "foo&bar=baz", "%", "ü"] {
+            assert_eq!(decode_url(&encode_url(string)), string);
+        }
+    }
+
+    #[test]
+    fn decode_url_with_invalid_escape() {
+        assert_eq!(decode_url("foo%"), "foo%");
+        assert_eq!(decode_url("foo%2"), "foo%2");
+        assert_eq!(decode_url("foo%zz"), "foo%zz");
+        assert_eq!(decode_url("foo+bar"), "foo bar");
+    }
+}
@@ -0,0 +1,50 @@
+use super::write_lines;
+use crate::{
+    build_graph::BuildGraph, context::Context, error::ApplicationError, ir::Configuration,
+};
+use std::collections::HashSet;
+
+pub async fn commands(
+    context: &Context,
+    configuration: &Configuration,
+    outputs: &[String],
+    final_only: bool,
+) -> Result<(), ApplicationError> {
+    let outputs = if outputs.is_empty() {
+        configuration
+            .default_outputs()
+            .iter()
+            .map(|output| output.as_ref())
+            .collect::<Vec<_>>()
+    } else {
+        outputs.iter().map(String::as_str).collect()
+    };
+
+    for output in &outputs {
+        if !configuration.outputs().contains_key(*output) {
+            return Err(ApplicationError::OutputNotFound(output.to_string()));
+        }
+    }
+
+    let graph = BuildGraph::new(configuration.outputs());
+    let paths = if final_only {
+        outputs.iter().map(|&output| output.into()).collect()
+    } else {
+        graph.reachable(outputs.iter().copied())
+    };
+    let mut ids = HashSet::new();
+    let mut commands = vec![];
+
+    for path in graph.sort()? {
+        if !paths.contains(&path) {
+            continue;
+        } else if let Some(build) = configuration.outputs().get(&path)
+            && ids.insert(build.id())
+            && let Some(rule) = build.rule()
+        {
+            commands.push(rule.command());
+        }
+    }
+
+    write_lines(context, commands).await
+}
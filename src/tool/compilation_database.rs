@@ -0,0 +1,48 @@
+use super::write_stdout;
+use crate::{context::Context, error::ApplicationError, ir::Configuration};
+use itertools::Itertools;
+use serde::Serialize;
+use std::env::current_dir;
+
+#[derive(Serialize)]
+struct Command<'a> {
+    directory: &'a str,
+    command: &'a str,
+    file: &'a str,
+    output: &'a str,
+}
+
+pub async fn compilation_database(
+    context: &Context,
+    configuration: &Configuration,
+    rules: &[String],
+) -> Result<(), ApplicationError> {
+    let directory = current_dir()?;
+    let directory = directory.to_string_lossy();
+
+    let commands = configuration
+        .outputs()
+        .values()
+        .unique_by(|build| build.id())
+        .filter_map(|build| {
+            let rule = build.rule()?;
+
+            if !rules.is_empty() && !rules.iter().any(|name| name == rule.name()) {
+                return None;
+            }
+
+            Some(Command {
+                directory: &directory,
+                command: rule.command(),
+                file: build.inputs().first()?,
+                output: &build.outputs()[0],
+            })
+        })
+        .sorted_by_key(|command| command.output)
+        .collect::<Vec<_>>();
+
+    let mut string = serde_json::to_string_pretty(&commands)?;
+    string.push('\n');
+
+    write_stdout(context, string).await
+}
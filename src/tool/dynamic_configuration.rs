@@ -0,0 +1,27 @@
+use crate::{
+    context::Context,
+    error::ApplicationError,
+    ir::{Configuration, DynamicBuild, DynamicConfiguration},
+};
+
+// Dynamic inputs are recorded in a database when dynamic modules are read
+// during builds.
+pub fn load_dynamic_configuration(
+    context: &Context,
+    configuration: &Configuration,
+) -> Result<DynamicConfiguration, ApplicationError> {
+    let mut outputs = Vec::new();
+
+    for (output, build) in configuration.outputs() {
+        if build.dynamic_module().is_none() || output != &build.outputs()[0] {
+            continue;
+        } else if let Some(inputs) = context.database().get_dynamic_inputs(build.id())? {
+            outputs.push((
+                output.clone(),
+                DynamicBuild::new(inputs.into_iter().map(From::from).collect()),
+            ));
+        }
+    }
+
+    Ok(DynamicConfiguration::new(outputs.into_iter().collect()))
+}
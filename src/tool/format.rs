@@ -0,0 +1,90 @@
+use super::write_lines;
+use crate::{
+    ast::{Comment, Module, Statement},
+    context::Context,
+    error::ApplicationError,
+    parse::parse,
+    print::print,
+};
+use std::{env::current_dir, path::PathBuf};
+
+pub async fn format(
+    context: &Context,
+    paths: &[PathBuf],
+    check: bool,
+) -> Result<(), ApplicationError> {
+    let directory = current_dir()?;
+    let mut unformatted_paths = vec![];
+
+    for path in paths {
+        let mut source = String::new();
+
+        context
+            .file_system()
+            .read_file_to_string(path, &mut source)
+            .await?;
+
+        let module = parse(&source)?;
+        let formatted = print(&module);
+
+        // Refuse to format files not to lose any comments.
+        if comments(&parse(&formatted)?) != comments(&module) {
+            return Err(ApplicationError::Other(format!(
+                "comments cannot be formatted in {}",
+                path.display()
+            )));
+        }
+
+        if formatted == source {
+            continue;
+        } else if check {
+            unformatted_paths.push(path.strip_prefix(&directory).unwrap_or(path));
+        } else {
+            context
+                .file_system()
+                .write_file(path, formatted.as_bytes())
+                .await?;
+        }
+    }
+
+    write_lines(
+        context,
+        unformatted_paths
+            .iter()
+            .map(|path| path.display().to_string()),
+    )
+    .await?;
+
+    if unformatted_paths.is_empty() {
+        Ok(())
+    } else {
+        Err(ApplicationError::Other(format!(
+            "{} build files not formatted",
+            unformatted_paths.len()
+        )))
+    }
+}
+
+fn comments(module: &Module) -> Vec<&Comment> {
+    module
+        .statements()
+        .iter()
+        .flat_map(|statement| {
+            let comments = match statement {
+                Statement::Build(build) => build.comments(),
+                Statement::Comment(comment) => return vec![comment],
+                Statement::Default(default) => default.comments(),
+                Statement::Rule(rule) => rule.comments(),
+                Statement::Include(_)
+                | Statement::Submodule(_)
+                | Statement::VariableDefinition(_) => return vec![],
+            };
+
+            comments
+                .trailing()
+                .into_iter()
+                .chain(comments.body().iter().map(|(_, comment)| comment))
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,119 @@
+use super::{dynamic_configuration::load_dynamic_configuration, rule_name, write_stdout};
+use crate::{
+    build_graph::BuildGraph,
+    context::Context,
+    error::ApplicationError,
+    ir::{Build, Configuration},
+};
+use itertools::Itertools;
+use std::{collections::HashMap, fmt::Write, sync::Arc};
+
+pub async fn graph(
+    context: &Context,
+    configuration: &Configuration,
+    outputs: &[String],
+    dynamic: bool,
+) -> Result<(), ApplicationError> {
+    let mut graph = BuildGraph::new(configuration.outputs());
+    let dynamic_configuration = if dynamic {
+        let dynamic_configuration = load_dynamic_configuration(context, configuration)?;
+        graph.insert_dynamic(&dynamic_configuration);
+        Some(dynamic_configuration)
+    } else {
+        None
+    };
+
+    for output in outputs {
+        if !configuration.outputs().contains_key(output.as_str()) {
+            return Err(ApplicationError::OutputNotFound(output.clone()));
+        }
+    }
+
+    let paths = (!outputs.is_empty()).then(|| graph.reachable(outputs.iter().map(String::as_str)));
+    let builds = configuration
+        .outputs()
+        .values()
+        .unique_by(|build| build.id())
+        .filter(|build| {
+            paths
+                .as_ref()
+                .map(|paths| paths.contains(&build.outputs()[0]))
+                .unwrap_or(true)
+        })
+        .sorted_by_key(|build| &build.outputs()[0])
+        .collect::<Vec<_>>();
+
+    let mut nodes = HashMap::<&str, usize>::new();
+    let mut string = String::new();
+
+    writeln!(string, "digraph turtle {{")?;
+    writeln!(string, "rankdir=\"LR\"")?;
+    writeln!(string, "node [fontsize=10, shape=box, height=0.25]")?;
+    writeln!(string, "edge [fontsize=10]")?;
+
+    for (index, build) in builds.iter().enumerate() {
+        let id = format!("b{index}");
+
+        writeln!(
+            string,
+            "{id} [label=\"{}\", shape=ellipse]",
+            escape(&format_build(build))
+        )?;
+
+        for output in build.outputs().iter().chain(build.implicit_outputs()) {
+            let node = write_node(&mut string, &mut nodes, output)?;
+            writeln!(string, "{id} -> {node}")?;
+        }
+
+        let dynamic_inputs = dynamic_configuration
+            .as_ref()
+            .and_then(|configuration| configuration.outputs().get(&build.outputs()[0]))
+            .map(|build| build.inputs())
+            .unwrap_or_default();
+
+        for (style, inputs) in [
+            ("", build.inputs()),
+            (" [style=dashed]", build.implicit_inputs()),
+            (" [style=dotted]", build.order_only_inputs()),
+            (" [style=bold]", dynamic_inputs),
+        ] {
+            for input in inputs {
+                let node = write_node(&mut string, &mut nodes, input)?;
+                writeln!(string, "{node} -> {id}{style}")?;
+            }
+        }
+    }
+
+    writeln!(string, "}}")?;
+
+    write_stdout(context, string).await
+}
+
+fn write_node<'a>(
+    string: &mut String,
+    nodes: &mut HashMap<&'a str, usize>,
+    path: &'a Arc<str>,
+) -> Result<String, ApplicationError> {
+    let index = if let Some(&index) = nodes.get(path.as_ref()) {
+        index
+    } else {
+        let index = nodes.len();
+        nodes.insert(path, index);
+        writeln!(string, "f{index} [label=\"{}\"]", escape(path))?;
+        index
+    };
+
+    Ok(format!("f{index}"))
+}
+
+fn format_build(build: &Build) -> String {
+    build
+        .rule()
+        .and_then(|rule| rule.description())
+        .unwrap_or_else(|| rule_name(build))
+        .into()
+}
+
+fn escape(string: &str) -> String {
+    string.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,32 @@
+use super::{dynamic_configuration::load_dynamic_configuration, write_paths};
+use crate::{
+    build_graph::BuildGraph, context::Context, error::ApplicationError, ir::Configuration,
+};
+use itertools::Itertools;
+
+pub async fn inputs(
+    context: &Context,
+    configuration: &Configuration,
+    outputs: &[String],
+    null: bool,
+) -> Result<(), ApplicationError> {
+    for output in outputs {
+        if !configuration.outputs().contains_key(output.as_str()) {
+            return Err(ApplicationError::OutputNotFound(output.clone()));
+        }
+    }
+
+    let mut graph = BuildGraph::new(configuration.outputs());
+    graph.insert_dynamic(&load_dynamic_configuration(context, configuration)?);
+
+    write_paths(
+        context,
+        graph
+            .reachable(outputs.iter().map(String::as_str))
+            .iter()
+            .filter(|path| !configuration.outputs().contains_key(*path))
+            .sorted(),
+        null,
+    )
+    .await
+}
@@ -0,0 +1,32 @@
+use super::write_stdout;
+use crate::{context::Context, error::ApplicationError, ir::Configuration};
+use itertools::Itertools;
+
+pub async fn last_failures(
+    context: &Context,
+    configuration: &Configuration,
+) -> Result<(), ApplicationError> {
+    let failures = context.database().get_failures()?;
+    let mut buffer = vec![];
+
+    for build in configuration
+        .outputs()
+        .values()
+        .filter(|build| failures.contains(&build.id()))
+        .unique_by(|build| build.id())
+        .sorted_by_key(|build| &build.outputs()[0])
+    {
+        buffer.extend(format!("FAILED: {}\n", build.outputs().iter().join(" ")).as_bytes());
+
+        if let Some((stdout, stderr)) = context.database().get_log(build.id())? {
+            for path in [stdout, stderr] {
+                context
+                    .file_system()
+                    .read_file(path.as_ref(), &mut buffer)
+                    .await?;
+            }
+        }
+    }
+
+    write_stdout(context, buffer).await
+}
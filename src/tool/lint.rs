@@ -0,0 +1,42 @@
+use super::{write_stderr, write_stdout};
+use crate::{context::Context, error::ApplicationError, lint::Diagnostic};
+use std::{env::current_dir, fmt::Write};
+
+pub async fn lint(context: &Context, diagnostics: &[Diagnostic]) -> Result<(), ApplicationError> {
+    write_stdout(context, format_diagnostics(diagnostics, "")?).await?;
+
+    if diagnostics.is_empty() {
+        Ok(())
+    } else {
+        Err(ApplicationError::Other(format!(
+            "{} lint warnings found",
+            diagnostics.len()
+        )))
+    }
+}
+
+pub async fn write_lint_warnings(
+    context: &Context,
+    diagnostics: &[Diagnostic],
+    prefix: &str,
+) -> Result<(), ApplicationError> {
+    write_stderr(
+        context,
+        format_diagnostics(diagnostics, &format!("{prefix}warning: "))?,
+    )
+    .await
+}
+
+fn format_diagnostics(
+    diagnostics: &[Diagnostic],
+    prefix: &str,
+) -> Result<String, ApplicationError> {
+    let directory = current_dir()?;
+    let mut string = String::new();
+
+    for diagnostic in diagnostics {
+        writeln!(string, "{prefix}{}", diagnostic.relative_to(&directory))?;
+    }
+
+    Ok(string)
+}
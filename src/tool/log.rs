@@ -0,0 +1,48 @@
+use crate::{context::Context, error::ApplicationError, hash_type::HashType, ir::Configuration};
+
+pub async fn log(
+    context: &Context,
+    configuration: &Configuration,
+    outputs: &[String],
+) -> Result<(), ApplicationError> {
+    let mut stdout = vec![];
+    let mut stderr = vec![];
+
+    for output in outputs {
+        let build = configuration
+            .outputs()
+            .get(output.as_str())
+            .ok_or_else(|| ApplicationError::OutputNotFound(output.clone()))?;
+        let Some((stdout_path, stderr_path)) = context.database().get_log(build.id())? else {
+            // Jobs without output have no logs.
+            if context
+                .database()
+                .get_hash(HashType::Timestamp, build.id())?
+                .is_some()
+                || context.database().get_failures()?.contains(&build.id())
+            {
+                continue;
+            }
+
+            return Err(ApplicationError::Other(format!(
+                "no log found for {output}"
+            )));
+        };
+
+        context
+            .file_system()
+            .read_file(stdout_path.as_ref(), &mut stdout)
+            .await?;
+        context
+            .file_system()
+            .read_file(stderr_path.as_ref(), &mut stderr)
+            .await?;
+    }
+
+    let mut console = context.console().lock().await;
+
+    console.write_stdout(&stdout).await?;
+    console.write_stderr(&stderr).await?;
+
+    Ok(())
+}
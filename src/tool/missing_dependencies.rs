@@ -0,0 +1,47 @@
+use super::{dynamic_configuration::load_dynamic_configuration, write_lines};
+use crate::{
+    build_graph::BuildGraph, context::Context, error::ApplicationError, ir::Configuration,
+};
+use itertools::Itertools;
+
+pub async fn missing_dependencies(
+    context: &Context,
+    configuration: &Configuration,
+) -> Result<(), ApplicationError> {
+    let graph = BuildGraph::new(configuration.outputs());
+    let dynamic_configuration = load_dynamic_configuration(context, configuration)?;
+    let mut dependencies = vec![];
+
+    for (consumer, build) in dynamic_configuration.outputs() {
+        let paths = graph.reachable([consumer.as_ref()]);
+
+        for input in build.inputs() {
+            if let Some(producer) = configuration.outputs().get(input)
+                && producer.rule().is_some()
+                && !paths.contains(input)
+            {
+                dependencies.push((consumer, input, &producer.outputs()[0]));
+            }
+        }
+    }
+
+    write_lines(
+        context,
+        dependencies
+            .iter()
+            .sorted()
+            .map(|(consumer, input, producer)| {
+                format!("missing dependency: {consumer} uses {input} (generated by {producer})")
+            }),
+    )
+    .await?;
+
+    if dependencies.is_empty() {
+        Ok(())
+    } else {
+        Err(ApplicationError::Other(format!(
+            "{} missing dependencies found",
+            dependencies.len()
+        )))
+    }
+}
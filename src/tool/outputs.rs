@@ -0,0 +1,35 @@
+use super::{dynamic_configuration::load_dynamic_configuration, write_paths};
+use crate::{
+    build_graph::BuildGraph, context::Context, error::ApplicationError, ir::Configuration,
+};
+use itertools::Itertools;
+
+pub async fn outputs(
+    context: &Context,
+    configuration: &Configuration,
+    inputs: &[String],
+    null: bool,
+) -> Result<(), ApplicationError> {
+    let mut graph = BuildGraph::new(configuration.outputs());
+    graph.insert_dynamic(&load_dynamic_configuration(context, configuration)?);
+
+    for input in inputs {
+        if !graph.contains(input) {
+            return Err(ApplicationError::InputNotFound(input.clone()));
+        }
+    }
+
+    write_paths(
+        context,
+        graph
+            .reverse_reachable(inputs.iter().map(String::as_str))
+            .iter()
+            .filter(|path| {
+                configuration.outputs().contains_key(*path)
+                    && !inputs.iter().any(|input| input.as_str() == path.as_ref())
+            })
+            .sorted(),
+        null,
+    )
+    .await
+}
@@ -0,0 +1,68 @@
+use super::{build_dependents, rule_name, write_stdout};
+use crate::{
+    build_graph::BuildGraph, context::Context, error::ApplicationError, ir::Configuration,
+};
+use std::fmt::Write;
+
+pub async fn query(
+    context: &Context,
+    configuration: &Configuration,
+    outputs: &[String],
+) -> Result<(), ApplicationError> {
+    let graph = BuildGraph::new(configuration.outputs());
+    let mut string = String::new();
+
+    for output in outputs {
+        let build = configuration.outputs().get(output.as_str());
+        let dependents = build_dependents(configuration, &graph, output);
+
+        if build.is_none() && dependents.is_empty() {
+            return Err(ApplicationError::OutputNotFound(output.clone()));
+        }
+
+        writeln!(string, "{}:", format_path(configuration, output))?;
+
+        if let Some(build) = build {
+            writeln!(string, "  input: {}", rule_name(build))?;
+
+            for (prefix, inputs) in [
+                ("", build.inputs()),
+                ("| ", build.implicit_inputs()),
+                ("|| ", build.order_only_inputs()),
+            ] {
+                for input in inputs {
+                    writeln!(
+                        string,
+                        "    {}{}",
+                        prefix,
+                        format_path(configuration, input)
+                    )?;
+                }
+            }
+
+            if let Some(inputs) = context.database().get_dynamic_inputs(build.id())? {
+                writeln!(string, "  dynamic inputs:")?;
+
+                for input in inputs {
+                    writeln!(string, "    {}", format_path(configuration, &input))?;
+                }
+            }
+        }
+
+        writeln!(string, "  outputs:")?;
+
+        for dependent in dependents {
+            writeln!(string, "    {}", format_path(configuration, dependent))?;
+        }
+    }
+
+    write_stdout(context, string).await
+}
+
+fn format_path(configuration: &Configuration, path: &str) -> String {
+    if let Some(source) = configuration.source_map().get(path) {
+        format!("{path} ({source})")
+    } else {
+        path.into()
+    }
+}
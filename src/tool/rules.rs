@@ -0,0 +1,24 @@
+use super::write_lines;
+use crate::{context::Context, error::ApplicationError, ir::Configuration};
+use itertools::Itertools;
+
+pub async fn rules(
+    context: &Context,
+    configuration: &Configuration,
+) -> Result<(), ApplicationError> {
+    write_lines(
+        context,
+        configuration
+            .rules()
+            .iter()
+            .sorted_by_key(|(name, _)| *name)
+            .map(|(name, description)| {
+                if let Some(description) = description {
+                    format!("{name}: {description}")
+                } else {
+                    name.to_string()
+                }
+            }),
+    )
+    .await
+}
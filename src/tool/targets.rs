@@ -0,0 +1,133 @@
+use super::{build_inputs, rule_name, write_stdout};
+use crate::{
+    build_graph::BuildGraph, context::Context, error::ApplicationError, ir::Configuration,
+};
+use itertools::Itertools;
+use std::{collections::HashSet, fmt::Write, sync::Arc};
+
+const DEFAULT_DEPTH: usize = 1;
+
+pub async fn targets(
+    context: &Context,
+    configuration: &Configuration,
+    arguments: &[String],
+) -> Result<(), ApplicationError> {
+    let mut string = String::new();
+
+    match arguments
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [] => write_tree(&mut string, configuration, DEFAULT_DEPTH)?,
+        ["depth"] => write_tree(&mut string, configuration, DEFAULT_DEPTH)?,
+        ["depth", depth] => write_tree(
+            &mut string,
+            configuration,
+            depth
+                .parse()
+                .map_err(|_| ApplicationError::Other(format!("invalid depth \"{depth}\"")))?,
+        )?,
+        ["rule"] => {
+            for input in source_files(configuration) {
+                writeln!(string, "{input}")?;
+            }
+        }
+        ["rule", rule] => {
+            for output in configuration
+                .outputs()
+                .iter()
+                .filter(|(_, build)| rule_name(build) == *rule)
+                .map(|(output, _)| output)
+                .sorted()
+            {
+                writeln!(string, "{output}")?;
+            }
+        }
+        ["all"] => {
+            for (output, build) in configuration
+                .outputs()
+                .iter()
+                .sorted_by_key(|(output, _)| *output)
+            {
+                writeln!(string, "{output}: {}", rule_name(build))?;
+            }
+        }
+        _ => {
+            return Err(ApplicationError::Other(format!(
+                "unknown targets mode \"{}\"",
+                arguments.join(" ")
+            )));
+        }
+    }
+
+    write_stdout(context, string).await
+}
+
+fn write_tree(
+    string: &mut String,
+    configuration: &Configuration,
+    depth: usize,
+) -> Result<(), ApplicationError> {
+    BuildGraph::new(configuration.outputs()).validate()?;
+
+    let inputs = configuration
+        .outputs()
+        .values()
+        .flat_map(|build| build_inputs(build))
+        .collect::<HashSet<_>>();
+
+    for output in configuration
+        .outputs()
+        .keys()
+        .filter(|output| !inputs.contains(output))
+        .sorted()
+    {
+        write_target(string, configuration, output, depth, 0)?;
+    }
+
+    Ok(())
+}
+
+// A depth of 0 means no limit.
+fn write_target(
+    string: &mut String,
+    configuration: &Configuration,
+    target: &str,
+    depth: usize,
+    indent: usize,
+) -> Result<(), ApplicationError> {
+    write!(string, "{}", "  ".repeat(indent))?;
+
+    if let Some(build) = configuration.outputs().get(target) {
+        writeln!(string, "{target}: {}", rule_name(build))?;
+
+        if depth != 1 {
+            for input in build_inputs(build) {
+                write_target(
+                    string,
+                    configuration,
+                    input,
+                    depth.saturating_sub(1),
+                    indent + 1,
+                )?;
+            }
+        }
+    } else {
+        writeln!(string, "{target}")?;
+    }
+
+    Ok(())
+}
+
+fn source_files(configuration: &Configuration) -> Vec<&Arc<str>> {
+    configuration
+        .outputs()
+        .values()
+        .flat_map(|build| build_inputs(build))
+        .filter(|input| !configuration.outputs().contains_key(*input))
+        .sorted()
+        .dedup()
+        .collect()
+}
@@ -0,0 +1,185 @@
+use serde_json::{Map, Value, json};
+use std::{fmt::Display, mem::take, sync::Mutex, time::Instant};
+
+const PROCESS_ID: u32 = 0;
+
+// A recorder of time slices in the Chrome trace event format. Slices are put
+// on the first lanes free during them so that lanes represent parallelism.
+#[derive(Debug)]
+pub struct Trace {
+    enabled: bool,
+    start_time: Instant,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    lanes: Vec<bool>,
+    events: Vec<Value>,
+}
+
+// A slice recorded when it is dropped
+#[derive(Debug)]
+pub struct Slice<'a> {
+    trace: &'a Trace,
+    name: String,
+    category: &'static str,
+    lane: usize,
+    start_time: Instant,
+    arguments: Map<String, Value>,
+}
+
+impl Slice<'_> {
+    pub fn with_argument(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.arguments.insert(key.into(), value.into());
+        self
+    }
+}
+
+impl Drop for Slice<'_> {
+    fn drop(&mut self) {
+        self.trace.finish(self);
+    }
+}
+
+impl Trace {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            start_time: Instant::now(),
+            state: Default::default(),
+        }
+    }
+
+    // Starts a slice only if tracing is enabled so that untraced runs allocate
+    // nothing for names and arguments.
+    pub fn start(&self, category: &'static str, name: impl Display) -> Option<Slice<'_>> {
+        if !self.enabled {
+            return None;
+        }
+
+        let lane = {
+            let lanes = &mut self.state.lock().unwrap().lanes;
+
+            if let Some(lane) = lanes.iter().position(|&busy| !busy) {
+                lanes[lane] = true;
+                lane
+            } else {
+                lanes.push(true);
+                lanes.len() - 1
+            }
+        };
+
+        Some(Slice {
+            trace: self,
+            name: name.to_string(),
+            category,
+            lane,
+            start_time: Instant::now(),
+            arguments: Default::default(),
+        })
+    }
+
+    fn finish(&self, slice: &mut Slice) {
+        let end_time = Instant::now();
+        let mut state = self.state.lock().unwrap();
+
+        state.lanes[slice.lane] = false;
+        state.events.push(json!({
+            "name": take(&mut slice.name),
+            "cat": slice.category,
+            "ph": "X",
+            "ts": (slice.start_time - self.start_time).as_micros() as u64,
+            "dur": (end_time - slice.start_time).as_micros() as u64,
+            "pid": PROCESS_ID,
+            "tid": slice.lane,
+            "args": take(&mut slice.arguments),
+        }));
+    }
+
+    pub fn to_json(&self) -> String {
+        let state = self.state.lock().unwrap();
+
+        json!({
+            "traceEvents": (0..state.lanes.len())
+                .map(|lane| {
+                    json!({
+                        "name": "thread_name",
+                        "ph": "M",
+                        "pid": PROCESS_ID,
+                        "tid": lane,
+                        "args": { "name": format!("slot {lane}") },
+                    })
+                })
+                .chain(state.events.iter().cloned())
+                .collect::<Vec<_>>(),
+        })
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lanes(trace: &Trace) -> Vec<(String, u64)> {
+        trace
+            .state
+            .lock()
+            .unwrap()
+            .events
+            .iter()
+            .map(|event| {
+                (
+                    event["name"].as_str().unwrap().into(),
+                    event["tid"].as_u64().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn allocate_lanes() {
+        let trace = Trace::new(true);
+
+        let foo = trace.start("job", "foo");
+        let bar = trace.start("job", "bar");
+        drop(foo);
+        drop(trace.start("job", "baz"));
+        drop(bar);
+
+        assert_eq!(
+            lanes(&trace),
+            [("foo".into(), 0), ("baz".into(), 0), ("bar".into(), 1)]
+        );
+    }
+
+    #[test]
+    fn record_nothing_if_disabled() {
+        let trace = Trace::new(false);
+
+        assert!(trace.start("job", "foo").is_none());
+        assert!(lanes(&trace).is_empty());
+    }
+
+    #[test]
+    fn serialize_events() {
+        let trace = Trace::new(true);
+
+        drop(
+            trace
+                .start("job", "foo")
+                .unwrap()
+                .with_argument("outputs", vec!["foo"]),
+        );
+
+        let value = serde_json::from_str::<Value>(&trace.to_json()).unwrap();
+        let events = value["traceEvents"].as_array().unwrap();
+
+        assert_eq!(events[0]["ph"], "M");
+        assert_eq!(events[1]["name"], "foo");
+        assert_eq!(events[1]["cat"], "job");
+        assert_eq!(events[1]["ph"], "X");
+        assert_eq!(events[1]["args"]["outputs"], json!(["foo"]));
+    }
+}